@@ -0,0 +1,211 @@
+#!/usr/bin/env python3
+"""Rebuilds a `/wiki` knowledge source index by crawling the source's site.
+
+Takes an existing index for the source's ID, name, color and base URL, and writes a new index listing
+every page on the site with its title, section headings and summary. Pages are found through the site's
+sitemap, or by following links from the base URL if it doesn't have one. Keywords from the existing
+index are kept for pages that are still there, since they hold the nicknames people search for.
+
+    python3 scripts/crawl_knowledge.py assets/knowledge/sigbots.json > data/knowledge/sigbots.json
+
+Pass `--in-place` to update the given index instead, e.g. to refresh the copy bundled with the bot.
+"""
+
+import argparse
+import json
+import sys
+import time
+import urllib.error
+import urllib.request
+import xml.etree.ElementTree as ElementTree
+from html.parser import HTMLParser
+from urllib.parse import urldefrag, urljoin, urlparse
+
+SUMMARY_LENGTH = 300
+SKIPPED_EXTENSIONS = (".png", ".jpg", ".jpeg", ".gif", ".svg", ".pdf", ".zip", ".css", ".js", ".json", ".xml")
+
+
+class PageParser(HTMLParser):
+    """Pulls the parts of a page that go into the index out of its HTML."""
+
+    def __init__(self):
+        super().__init__(convert_charrefs=True)
+        self.title = ""
+        self.h1 = ""
+        self.headings = []
+        self.description = ""
+        self.paragraphs = []
+        self.links = []
+        self.capturing = None
+        self.buffer = []
+        self.skipping = 0
+
+    def handle_starttag(self, tag, attrs):
+        attrs = dict(attrs)
+        if tag in {"script", "style", "nav", "header", "footer", "aside"}:
+            self.skipping += 1
+        elif tag == "meta" and attrs.get("name") == "description":
+            self.description = attrs.get("content") or ""
+        elif tag == "a" and attrs.get("href"):
+            self.links.append(attrs["href"])
+        elif tag in {"title", "h1", "h2", "h3", "p"} and self.capturing is None and not self.skipping:
+            self.capturing = tag
+            self.buffer = []
+
+    def handle_endtag(self, tag):
+        if tag in {"script", "style", "nav", "header", "footer", "aside"}:
+            self.skipping = max(self.skipping - 1, 0)
+        elif tag == self.capturing:
+            text = " ".join("".join(self.buffer).split())
+            self.capturing = None
+            if not text:
+                return
+
+            if tag == "title":
+                self.title = text
+            elif tag == "h1" and not self.h1:
+                self.h1 = text
+            elif tag in {"h2", "h3"}:
+                self.headings.append(text)
+            elif tag == "p":
+                self.paragraphs.append(text)
+
+    def handle_data(self, data):
+        if self.capturing is not None:
+            self.buffer.append(data)
+
+
+def fetch(url):
+    request = urllib.request.Request(url, headers={"User-Agent": "robostats-knowledge-crawler"})
+    with urllib.request.urlopen(request) as response:
+        if "html" not in response.headers.get("Content-Type", "html") and not url.endswith(".xml"):
+            return None
+        return response.read().decode("utf-8", errors="replace")
+
+
+def sitemap_urls(url, delay):
+    """Lists every page in a sitemap, following sitemap indexes."""
+    try:
+        root = ElementTree.fromstring(fetch(url))
+    except (urllib.error.URLError, ElementTree.ParseError, TypeError):
+        return []
+
+    namespace = {"sitemap": "http://www.sitemaps.org/schemas/sitemap/0.9"}
+    if root.tag.endswith("sitemapindex"):
+        urls = []
+        for location in root.findall("sitemap:sitemap/sitemap:loc", namespace):
+            time.sleep(delay)
+            urls.extend(sitemap_urls(location.text.strip(), delay))
+        return urls
+
+    return [location.text.strip() for location in root.findall("sitemap:url/sitemap:loc", namespace)]
+
+
+def path_of(url, base_url):
+    """Returns a URL's path relative to the base URL, or `None` if it's somewhere else."""
+    url = urldefrag(url)[0]
+    base = urlparse(base_url)
+    parsed = urlparse(url)
+    if parsed.netloc != base.netloc or not parsed.path.startswith(base.path):
+        return None
+    if parsed.path.lower().endswith(SKIPPED_EXTENSIONS):
+        return None
+
+    path = parsed.path[len(base.path.rstrip("/")):] or "/"
+    return path.rstrip("/") or "/"
+
+
+def summarize(page):
+    summary = page.description or next((p for p in page.paragraphs if len(p) > 40), "")
+    if len(summary) > SUMMARY_LENGTH:
+        summary = summary[:SUMMARY_LENGTH].rsplit(" ", 1)[0] + "…"
+    return summary
+
+
+def crawl(catalog, delay, limit):
+    base_url = catalog["base_url"].rstrip("/")
+    old_keywords = {article["path"]: article.get("keywords", []) for article in catalog["articles"]}
+
+    queue = [base_url + "/"]
+    queue.extend(sitemap_urls(f"{base_url}/sitemap.xml", delay))
+    follow_links = len(queue) == 1
+
+    seen = set()
+    articles = []
+    while queue and len(articles) < limit:
+        url = queue.pop(0)
+        path = path_of(url, base_url)
+        if path is None or path in seen:
+            continue
+        seen.add(path)
+
+        time.sleep(delay)
+        try:
+            source = fetch(base_url + path)
+        except urllib.error.URLError as err:
+            print(f"Skipped {path}: {err}", file=sys.stderr)
+            continue
+        if source is None:
+            continue
+
+        page = PageParser()
+        page.feed(source)
+        title = page.h1 or page.title.split(" | ")[0].split(" - ")[0]
+        if not title:
+            continue
+
+        print(f"Indexed {path}", file=sys.stderr)
+        articles.append({
+            "title": title,
+            "path": path,
+            "headings": list(dict.fromkeys(page.headings)),
+            "keywords": old_keywords.get(path, []),
+            "summary": summarize(page),
+        })
+
+        if follow_links:
+            queue.extend(urljoin(base_url + path, link) for link in page.links)
+
+    return {**catalog, "articles": articles}
+
+
+def to_json(value, indent=0):
+    """Formats an index the way the bundled ones are written, with lists of plain values kept on one line."""
+    padding = " " * (indent + 4)
+    if isinstance(value, dict):
+        items = [f"{padding}{json.dumps(key)}: {to_json(item, indent + 4)}" for key, item in value.items()]
+        return "{\n" + ",\n".join(items) + "\n" + " " * indent + "}"
+    if isinstance(value, list) and any(isinstance(item, (dict, list)) for item in value):
+        items = [f"{padding}{to_json(item, indent + 4)}" for item in value]
+        return "[\n" + ",\n".join(items) + "\n" + " " * indent + "]"
+    if isinstance(value, list):
+        return "[" + ", ".join(json.dumps(item, ensure_ascii=False) for item in value) + "]"
+    return json.dumps(value, ensure_ascii=False)
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("index", help="existing index for the source, e.g. assets/knowledge/sigbots.json")
+    parser.add_argument("--in-place", action="store_true", help="overwrite the given index")
+    parser.add_argument("--delay", type=float, default=0.5, help="seconds to wait between requests (default: 0.5)")
+    parser.add_argument("--limit", type=int, default=2000, help="most pages to index (default: 2000)")
+    args = parser.parse_args()
+
+    with open(args.index, encoding="utf-8") as index:
+        catalog = json.load(index)
+
+    catalog = crawl(catalog, args.delay, args.limit)
+    if not catalog["articles"]:
+        sys.exit(f"No pages found at {catalog['base_url']}.")
+
+    output = to_json(catalog) + "\n"
+    if args.in_place:
+        with open(args.index, "w", encoding="utf-8") as index:
+            index.write(output)
+    else:
+        sys.stdout.write(output)
+    print(f"Indexed {len(catalog['articles'])} pages.", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    main()
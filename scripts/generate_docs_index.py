@@ -0,0 +1,400 @@
+#!/usr/bin/env python3
+"""Generates a `/docs` index from a C++ project's Doxygen XML and Sphinx documentation.
+
+Signatures, descriptions and parameters come from the Doxygen XML that the project's Sphinx docs are
+built from (through Breathe). Links come from the Sphinx inventory (`objects.inv`) of the published docs,
+so every symbol links straight to its own entry on its page. For LemLib:
+
+    doxygen                     # in a LemLib checkout, with GENERATE_XML = YES
+    python3 scripts/generate_docs_index.py path/to/doxygen/xml \
+        --inventory https://lemlib.readthedocs.io/en/stable/objects.inv \
+        --project LemLib --version 0.5 --namespace lemlib \
+        --base-url https://lemlib.readthedocs.io/en/stable > assets/docs/lemlib.json
+"""
+
+import argparse
+import json
+import re
+import sys
+import urllib.request
+import xml.etree.ElementTree as ElementTree
+import zlib
+from pathlib import Path
+
+# IDs of fundamental types in Sphinx's C++ domain, which names each declaration's anchor after it.
+FUNDAMENTAL_TYPE_IDS = {
+    "void": "v", "bool": "b", "char": "c", "signed char": "a", "unsigned char": "h", "wchar_t": "w",
+    "char8_t": "Du", "char16_t": "Ds", "char32_t": "Di",
+    "short": "s", "short int": "s", "signed short": "s", "signed short int": "s",
+    "unsigned short": "t", "unsigned short int": "t",
+    "int": "i", "signed": "i", "signed int": "i", "unsigned": "j", "unsigned int": "j",
+    "long": "l", "long int": "l", "signed long": "l", "signed long int": "l",
+    "unsigned long": "m", "unsigned long int": "m",
+    "long long": "x", "long long int": "x", "signed long long": "x", "signed long long int": "x",
+    "unsigned long long": "y", "unsigned long long int": "y",
+    "float": "f", "double": "d", "long double": "e", "auto": "Da", "std::nullptr_t": "Dn",
+}
+FUNDAMENTAL_WORDS = {"void", "bool", "char", "wchar_t", "char8_t", "char16_t", "char32_t", "short", "int",
+                     "signed", "unsigned", "long", "float", "double", "auto"}
+TOKEN = re.compile(r"\s*(::|&&|[A-Za-z_]\w*|\d+|[<>,*&()\[\]])")
+
+
+class UnsupportedType(Exception):
+    """A type that the anchor generator can't name, such as a function type."""
+
+
+def tokenize(text):
+    tokens = []
+    position = 0
+    text = text.strip()
+    while position < len(text):
+        match = TOKEN.match(text, position)
+        if not match:
+            raise UnsupportedType(text)
+        tokens.append(match.group(1))
+        position = match.end()
+    return tokens
+
+
+def identifier_id(name):
+    return "St" if name == "std" else f"{len(name)}{name}"
+
+
+def nested_name_id(parts, modifiers=""):
+    """ID of a (possibly qualified) name, where each part is an identifier and its template arguments."""
+    ids = "".join(identifier_id(name) + (f"I{''.join(args)}E" if args is not None else "") for name, args in parts)
+    return f"N{modifiers}{ids}E" if len(parts) > 1 else ids
+
+
+class TypeParser:
+    """Turns a C++ type such as `const std::vector<Pose>&` into its Sphinx ID (`RKNSt6vectorI4PoseEE`)."""
+
+    def __init__(self, text):
+        self.tokens = tokenize(text)
+        self.position = 0
+
+    def peek(self):
+        return self.tokens[self.position] if self.position < len(self.tokens) else None
+
+    def take(self):
+        token = self.peek()
+        self.position += 1
+        return token
+
+    def parse(self):
+        type_id = self.type()
+        if self.peek() is not None:
+            raise UnsupportedType(" ".join(self.tokens))
+        return type_id
+
+    def type(self):
+        const = volatile = False
+        fundamental = []
+        name = None
+
+        while True:
+            token = self.peek()
+            if token == "const":
+                const = True
+            elif token == "volatile":
+                volatile = True
+            elif token in FUNDAMENTAL_WORDS and name is None:
+                fundamental.append(token)
+            elif token in {"struct", "class", "enum", "typename"}:
+                pass
+            elif token is not None and (token == "::" or token[0].isalpha() or token[0] == "_") \
+                    and name is None and not fundamental:
+                name = self.nested_name()
+                continue
+            else:
+                break
+            self.take()
+
+        if fundamental:
+            key = " ".join(fundamental)
+            if key not in FUNDAMENTAL_TYPE_IDS:
+                raise UnsupportedType(key)
+            type_id = FUNDAMENTAL_TYPE_IDS[key]
+        elif name is not None:
+            type_id = "Dn" if name == [("std", None), ("nullptr_t", None)] else nested_name_id(name)
+        else:
+            raise UnsupportedType(" ".join(self.tokens))
+
+        type_id = ("V" if volatile else "") + ("K" if const else "") + type_id
+
+        # Declarators wrap the type from the inside out: `T* const&` is a reference to a const pointer to T.
+        while self.peek() in {"*", "&", "&&"}:
+            declarator = self.take()
+            if declarator == "*":
+                pointer_volatile = pointer_const = False
+                while self.peek() in {"const", "volatile"}:
+                    if self.take() == "const":
+                        pointer_const = True
+                    else:
+                        pointer_volatile = True
+                type_id = "P" + ("V" if pointer_volatile else "") + ("C" if pointer_const else "") + type_id
+            else:
+                type_id = ("R" if declarator == "&" else "O") + type_id
+
+        if self.peek() in {"(", "["}:
+            raise UnsupportedType(" ".join(self.tokens))
+        return type_id
+
+    def nested_name(self):
+        parts = []
+        if self.peek() == "::":
+            self.take()
+        while True:
+            name = self.take()
+            args = None
+            if self.peek() == "<":
+                self.take()
+                args = []
+                while self.peek() != ">":
+                    if self.peek() is not None and self.peek().isdigit():
+                        args.append(f"XL{self.take()}EE")
+                    else:
+                        args.append(self.type())
+                    if self.peek() == ",":
+                        self.take()
+                    elif self.peek() != ">":
+                        raise UnsupportedType(" ".join(self.tokens))
+                self.take()
+            parts.append((name, args))
+
+            if self.peek() != "::":
+                return parts
+            self.take()
+
+
+def declaration_id(qualified_name, parameter_types=None, const=False):
+    """Sphinx's anchor for a class (no parameter list) or function declaration."""
+    parts = [(name, None) for name in qualified_name.split("::")]
+    if parameter_types is None:
+        return "_CPPv4" + nested_name_id(parts)
+
+    parameters = "".join(TypeParser(text).parse() for text in parameter_types) or "v"
+    return "_CPPv4" + nested_name_id(parts, "K" if const else "") + parameters
+
+
+def read_inventory(source):
+    """Reads a Sphinx inventory, returning the page and anchor of every documented C++ symbol."""
+    if re.match(r"https?://", source):
+        with urllib.request.urlopen(source) as response:
+            data = response.read()
+    else:
+        data = Path(source).read_bytes()
+
+    # Four header lines, followed by the zlib-compressed entries.
+    header_end = 0
+    for _ in range(4):
+        header_end = data.index(b"\n", header_end) + 1
+    lines = zlib.decompress(data[header_end:]).decode("utf-8").splitlines()
+
+    entries = []
+    for line in lines:
+        match = re.match(r"(.+?)\s+(\S+):(\S+)\s+(-?\d+)\s+(\S*)\s+(.*)", line)
+        if not match or match.group(2) != "cpp":
+            continue
+
+        name, uri = match.group(1), match.group(5)
+        if uri.endswith("$"):
+            uri = uri[:-1] + name
+        entries.append((name, "/" + uri))
+    return entries
+
+
+def text_of(element, skipped=("parameterlist", "simplesect")):
+    """Text of a Doxygen description, without its parameter and return value lists."""
+    if element is None:
+        return ""
+
+    parts = [element.text or ""]
+    for child in element:
+        if child.tag not in skipped:
+            parts.append(text_of(child, skipped))
+            if child.tag == "para":
+                parts.append("\n\n")
+        parts.append(child.tail or "")
+    return "".join(parts)
+
+
+def clean(text):
+    paragraphs = re.split(r"\n\s*\n", text)
+    return "\n\n".join(" ".join(paragraph.split()) for paragraph in paragraphs if paragraph.strip())
+
+
+def clean_type(text):
+    return re.sub(r"\s+([*&]+)", r"\1", " ".join(text.split())).replace("< ", "<").replace(" >", ">")
+
+
+def description(member):
+    return clean(text_of(member.find("briefdescription"))) \
+        or clean(text_of(member.find("detaileddescription"))).split("\n\n")[0]
+
+
+def parameters(member):
+    documented = {}
+    for item in member.iterfind("detaileddescription//parameterlist[@kind='param']/parameteritem"):
+        for name in item.iterfind("parameternamelist/parametername"):
+            documented[clean(text_of(name))] = clean(text_of(item.find("parameterdescription")))
+
+    return [
+        {"name": clean(text_of(param.find("declname"))), "description": documented.get(clean(text_of(param.find("declname"))), "")}
+        for param in member.iterfind("param")
+        if param.find("declname") is not None
+    ]
+
+
+def signature(member):
+    arguments = []
+    for param in member.iterfind("param"):
+        argument = clean_type(text_of(param.find("type")))
+        if param.find("declname") is not None:
+            argument += " " + clean(text_of(param.find("declname")))
+        if param.find("defval") is not None:
+            argument += " = " + clean(text_of(param.find("defval")))
+        arguments.append(argument)
+
+    return_type = clean_type(text_of(member.find("type")))
+    name = clean(text_of(member.find("name")))
+    const = " const" if member.get("const") == "yes" else ""
+    return f"{return_type} {name}({', '.join(arguments)}){const}".strip()
+
+
+def member_anchor(qualified_name, member):
+    parameter_types = [text_of(param.find("type")) for param in member.iterfind("param")]
+    return declaration_id(qualified_name, parameter_types, member.get("const") == "yes")
+
+
+def read_symbols(xml_directory, namespace):
+    """Reads every documented public class, struct and function from Doxygen's XML output."""
+    xml_directory = Path(xml_directory)
+    index = ElementTree.parse(xml_directory / "index.xml").getroot()
+
+    symbols = []
+    for compound in index.iterfind("compound"):
+        if compound.get("kind") not in {"class", "struct", "namespace"}:
+            continue
+        definition = ElementTree.parse(xml_directory / f"{compound.get('refid')}.xml").getroot().find("compounddef")
+        compound_name = clean(text_of(definition.find("compoundname")))
+        if namespace and not (compound_name == namespace or compound_name.startswith(namespace + "::")):
+            continue
+
+        kind = definition.get("kind")
+        members = [
+            member for member in definition.iterfind("sectiondef/memberdef[@kind='function']")
+            if member.get("prot") == "public"
+        ]
+        constructors = [member for member in members if clean(text_of(member.find("name"))) == compound_name.split("::")[-1]]
+
+        if kind in {"class", "struct"}:
+            constructor = constructors[0] if constructors else None
+            symbols.append({
+                "name": compound_name,
+                "kind": kind,
+                "signature": signature(constructor) if constructor is not None else f"{kind} {compound_name}",
+                "description": description(definition),
+                "anchor": declaration_id(compound_name),
+                "parameters": parameters(constructor) if constructor is not None else [],
+            })
+
+        for member in members:
+            name = clean(text_of(member.find("name")))
+            if member in constructors or name.startswith(("~", "operator")) or not description(member):
+                continue
+
+            qualified_name = f"{compound_name}::{name}"
+            try:
+                anchor = member_anchor(qualified_name, member)
+            except UnsupportedType:
+                anchor = None
+
+            symbols.append({
+                "name": qualified_name,
+                "kind": "method" if kind in {"class", "struct"} else "function",
+                "signature": signature(member),
+                "description": description(member),
+                "anchor": anchor,
+                "parameters": parameters(member),
+            })
+
+    return symbols
+
+
+def link(symbols, inventory):
+    """Points each symbol at its entry in the published docs, dropping symbols that aren't published."""
+    anchors = {uri.split("#", 1)[1]: uri for _, uri in inventory if "#" in uri}
+    pages = {}
+    for name, uri in inventory:
+        pages.setdefault(name, []).append(uri)
+
+    linked = []
+    for symbol in symbols:
+        anchor = symbol.pop("anchor")
+        uris = pages.get(symbol["name"], [])
+        if anchor in anchors:
+            symbol["path"] = anchors[anchor]
+        elif len(uris) == 1:
+            symbol["path"] = uris[0]
+        elif uris:
+            # An overload whose anchor couldn't be worked out, so it links to the page it's on.
+            symbol["path"] = uris[0].split("#", 1)[0]
+            print(f"Linked {symbol['name']} to its page only: {symbol['signature']}", file=sys.stderr)
+        else:
+            print(f"Skipped {symbol['name']}: not in the published docs", file=sys.stderr)
+            continue
+
+        linked.append({key: symbol[key] for key in ["name", "kind", "signature", "description", "path", "parameters"]})
+    return linked
+
+
+def to_json(index):
+    """Formats an index the way the bundled one is written, with each parameter on one line."""
+    symbols = []
+    for symbol in index["symbols"]:
+        lines = [
+            f"            {json.dumps(key)}: {json.dumps(value, ensure_ascii=False)}"
+            for key, value in symbol.items()
+            if key != "parameters"
+        ]
+        # Symbols without parameters leave the list out, since it defaults to empty.
+        if symbol.get("parameters"):
+            parameters = ",\n".join(
+                "                { " + ", ".join(
+                    f"{json.dumps(key)}: {json.dumps(value, ensure_ascii=False)}" for key, value in parameter.items()
+                ) + " }"
+                for parameter in symbol["parameters"]
+            )
+            lines.append(f'            "parameters": [\n{parameters}\n            ]')
+        symbols.append("        {\n" + ",\n".join(lines) + "\n        }")
+
+    header = json.dumps({key: value for key, value in index.items() if key != "symbols"}, indent=4, ensure_ascii=False)
+    return header[:-2] + ',\n    "symbols": [\n' + ",\n".join(symbols) + "\n    ]\n}\n"
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("xml", help="directory of Doxygen XML output")
+    parser.add_argument("--inventory", required=True, help="path or URL of the published docs' objects.inv")
+    parser.add_argument("--project", required=True, help="project name, e.g. LemLib")
+    parser.add_argument("--version", required=True, help="version of the project being indexed")
+    parser.add_argument("--base-url", required=True, help="URL that the inventory's paths are relative to")
+    parser.add_argument("--namespace", help="only index symbols in this namespace, e.g. lemlib")
+    args = parser.parse_args()
+
+    symbols = link(read_symbols(args.xml, args.namespace), read_inventory(args.inventory))
+    if not symbols:
+        sys.exit("No documented symbols found.")
+
+    sys.stdout.write(to_json({
+        "project": args.project,
+        "version": args.version,
+        "base_url": args.base_url.rstrip("/"),
+        "symbols": symbols,
+    }))
+    print(f"Indexed {len(symbols)} symbols.", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,262 @@
+#!/usr/bin/env python3
+"""Builds a Q&A snapshot for `/qna` from RobotEvents Q&A pages or JSON exports.
+
+Inputs can be saved question pages (`.html`), JSON exports (`.json`, either a snapshot in the bot's own
+format or a list of questions), or directories of either. With `--fetch`, the season's Q&A is downloaded
+from RobotEvents instead. Questions that haven't been answered yet are left out, and questions found in
+several inputs are merged by question number, later inputs winning.
+
+    python3 scripts/import_qna.py --season 2024-2025 --game "High Stakes" --fetch > data/qna.json
+    python3 scripts/import_qna.py --season 2024-2025 --game "High Stakes" saved-pages/ > data/qna.json
+"""
+
+import argparse
+import datetime
+import html
+import json
+import re
+import sys
+import time
+import urllib.request
+from html.parser import HTMLParser
+from pathlib import Path
+
+ROBOTEVENTS = "https://www.robotevents.com"
+QUESTION_PATH = re.compile(r"/(\w+)/(\d{4}-\d{4})/QA/(\d+)")
+VOID_ELEMENTS = {"area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr"}
+BLOCK_ELEMENTS = {"p", "div", "li", "br", "blockquote", "pre", "tr", "h1", "h2", "h3", "h4", "h5", "h6"}
+HTML_TAG = re.compile(r"</?(?:p|br|div|span|a|b|i|u|em|strong|ul|ol|li|blockquote|pre|code|img|h\d)\b[^>]*>", re.IGNORECASE)
+MONTHS = "Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec"
+
+
+class Node:
+    def __init__(self, tag, attrs, parent=None):
+        self.tag = tag
+        self.attrs = dict(attrs)
+        self.parent = parent
+        self.children = []
+
+    @property
+    def classes(self):
+        return set((self.attrs.get("class") or "").split())
+
+    def walk(self):
+        for child in self.children:
+            if isinstance(child, Node):
+                yield child
+                yield from child.walk()
+
+    def find(self, predicate):
+        return next((node for node in self.walk() if predicate(node)), None)
+
+    def text(self):
+        """Returns the node's text, keeping paragraph breaks."""
+        parts = []
+        for child in self.children:
+            if isinstance(child, str):
+                parts.append(child)
+            else:
+                text = child.text()
+                parts.append(f"\n\n{text}\n\n" if child.tag in BLOCK_ELEMENTS else text)
+
+        paragraphs = re.split(r"\n\s*\n", "".join(parts))
+        return "\n\n".join(" ".join(paragraph.split()) for paragraph in paragraphs if paragraph.strip())
+
+
+class TreeBuilder(HTMLParser):
+    def __init__(self):
+        super().__init__(convert_charrefs=True)
+        self.root = Node("document", [])
+        self.current = self.root
+
+    def handle_starttag(self, tag, attrs):
+        node = Node(tag, attrs, self.current)
+        self.current.children.append(node)
+        if tag not in VOID_ELEMENTS:
+            self.current = node
+
+    def handle_endtag(self, tag):
+        # Close the nearest matching element, tolerating the unclosed tags that real pages are full of.
+        node = self.current
+        while node is not self.root and node.tag != tag:
+            node = node.parent
+        if node is not self.root:
+            self.current = node.parent
+
+    def handle_data(self, data):
+        self.current.children.append(data)
+
+
+def parse_html(source):
+    builder = TreeBuilder()
+    builder.feed(source)
+    return builder.root
+
+
+def parse_date(text):
+    """Finds a date in text such as "Answered Aug 14, 2023", returning it as YYYY-MM-DD."""
+    match = re.search(r"\d{4}-\d{2}-\d{2}", text)
+    if match:
+        return match.group(0)
+
+    for pattern, date_format in [
+        (rf"(?:{MONTHS})\w* \d{{1,2}}, \d{{4}}", "%b %d, %Y"),
+        (rf"\d{{1,2}}-(?:{MONTHS})-\d{{4}}", "%d-%b-%Y"),
+    ]:
+        match = re.search(pattern, text)
+        if match:
+            # Full month names ("August") are shortened so that one format handles both.
+            value = re.sub(rf"({MONTHS})\w*", r"\1", match.group(0))
+            return datetime.datetime.strptime(value, date_format).date().isoformat()
+
+    return None
+
+
+def question_from_html(source, url=None):
+    """Reads a single RobotEvents Q&A question page."""
+    document = parse_html(source)
+
+    if url is None:
+        canonical = document.find(lambda node: node.tag == "link" and node.attrs.get("rel") == "canonical")
+        url = canonical.attrs.get("href") if canonical else None
+    match = QUESTION_PATH.search(url or "")
+    if not match:
+        return None
+
+    question = document.find(lambda node: "question" in node.classes)
+    answer = document.find(lambda node: "answer" in node.classes and "approved" in node.classes) \
+        or document.find(lambda node: "answer" in node.classes)
+    if question is None or answer is None:
+        return None
+
+    title = question.find(lambda node: node.tag in {"h1", "h2", "h3", "h4"})
+    question_body = question.find(lambda node: "content-body" in node.classes)
+    answer_body = answer.find(lambda node: "content-body" in node.classes)
+    if title is None or question_body is None or answer_body is None or not answer_body.text():
+        return None
+
+    timestamp = answer.find(lambda node: node.tag == "time" and "datetime" in node.attrs)
+    answered_at = parse_date(timestamp.attrs["datetime"]) if timestamp else None
+    if answered_at is None:
+        answered_at = parse_date(answer.text().replace(answer_body.text(), ""))
+
+    return {
+        "id": int(match.group(3)),
+        "title": title.text(),
+        "question": question_body.text(),
+        "answer": answer_body.text(),
+        "answered_at": answered_at,
+        "url": f"{ROBOTEVENTS}{match.group(0)}",
+    }
+
+
+def questions_from_json(data):
+    """Reads questions from a snapshot in the bot's format, or from a list of exported questions."""
+    entries = data["questions"] if isinstance(data, dict) else data
+
+    for entry in entries:
+        answer = entry.get("answer")
+        if not answer:
+            continue
+
+        # Other exports use camelCase keys and may leave HTML in the question and answer. Plain text is kept
+        # as is, since rule references like "<SG3>" look like tags.
+        clean = lambda value: parse_html(value).text() if HTML_TAG.search(value) else value
+        yield {
+            "id": int(entry["id"]),
+            "title": html.unescape(entry["title"]),
+            "question": clean(entry["question"]),
+            "answer": clean(answer),
+            "answered_at": parse_date(str(entry.get("answered_at") or entry.get("answeredAt") or "")),
+            "url": entry["url"],
+        }
+
+
+def read_inputs(paths):
+    for path in paths:
+        path = Path(path)
+        files = sorted(path.rglob("*")) if path.is_dir() else [path]
+        for file in files:
+            if file.suffix == ".json":
+                yield from questions_from_json(json.loads(file.read_text(encoding="utf-8")))
+            elif file.suffix in {".html", ".htm"}:
+                question = question_from_html(file.read_text(encoding="utf-8"))
+                if question is None:
+                    print(f"Skipped {file}: not an answered Q&A question page.", file=sys.stderr)
+                else:
+                    yield question
+
+
+def fetch(url):
+    request = urllib.request.Request(url, headers={"User-Agent": "robostats-qna-import"})
+    with urllib.request.urlopen(request) as response:
+        return response.read().decode("utf-8")
+
+
+def fetch_questions(program, season, delay):
+    """Downloads every question listed in a season's Q&A, going easy on RobotEvents between requests."""
+    question_urls = []
+    page = 1
+    while True:
+        listing = fetch(f"{ROBOTEVENTS}/{program}/{season}/QA?page={page}")
+        links = [
+            f"{ROBOTEVENTS}{match.group(0)}"
+            for match in QUESTION_PATH.finditer(listing)
+            if match.group(2) == season
+        ]
+        new_links = [link for link in dict.fromkeys(links) if link not in question_urls]
+        if not new_links:
+            break
+
+        question_urls.extend(new_links)
+        page += 1
+        time.sleep(delay)
+
+    for index, url in enumerate(question_urls, start=1):
+        print(f"Fetching question {index} of {len(question_urls)}", file=sys.stderr)
+        question = question_from_html(fetch(url), url)
+        if question is not None:
+            yield question
+        time.sleep(delay)
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("inputs", nargs="*", help="saved question pages, JSON exports, or directories of them")
+    parser.add_argument("--season", required=True, help='season years, e.g. "2024-2025"')
+    parser.add_argument("--game", required=True, help="name of the season's game")
+    parser.add_argument("--program", default="VRC", help="program code in Q&A links (default: VRC)")
+    parser.add_argument("--fetch", action="store_true", help="download the season's Q&A from RobotEvents")
+    parser.add_argument("--delay", type=float, default=1.0, help="seconds to wait between requests (default: 1)")
+    args = parser.parse_args()
+
+    if not args.inputs and not args.fetch:
+        parser.error("give at least one input, or --fetch")
+
+    questions = {}
+    for question in read_inputs(args.inputs):
+        questions[question["id"]] = question
+    if args.fetch:
+        for question in fetch_questions(args.program, args.season, args.delay):
+            questions[question["id"]] = question
+
+    if not questions:
+        sys.exit("No answered questions found.")
+
+    json.dump(
+        {
+            "season": args.season,
+            "game": args.game,
+            "exported_at": datetime.date.today().isoformat(),
+            "questions": [questions[id] for id in sorted(questions)],
+        },
+        sys.stdout,
+        indent=4,
+        ensure_ascii=False,
+    )
+    sys.stdout.write("\n")
+    print(f"Imported {len(questions)} questions.", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    main()
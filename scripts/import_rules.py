@@ -0,0 +1,165 @@
+#!/usr/bin/env python3
+"""Imports the rules from a game manual into a rule book for `/rule`.
+
+The manual PDF is converted to text with poppler's `pdftotext` first, which keeps the page breaks (form
+feeds) that the rule book's page numbers come from:
+
+    pdftotext -layout game-manual.pdf manual.txt
+    python3 scripts/import_rules.py manual.txt --season 2024-2025 --game "High Stakes" \
+        --version 3.0 --manual-url https://example.com/game-manual.pdf > data/rules/2024-2025.json
+
+Rules start at a line beginning with a bracketed rule ID followed by the rule's title (e.g.
+"<SG3> Horizontal expansion is limited."), and run until the next rule or section heading. A
+"Violation Notes:" paragraph inside a rule is stored separately as its violation notes.
+"""
+
+import argparse
+import json
+import re
+import sys
+
+RULE_START = re.compile(r"^\s*<([A-Z]{1,4}\d{1,3}[a-z]?)>\s+(.+?)\.(?:\s+(.*))?$")
+VIOLATION_START = re.compile(r"^\s*Violation Notes?:\s*(.*)$", re.IGNORECASE)
+PAGE_FOOTER = re.compile(r"^\s*\d+\s*$")
+
+# Manual sections, keyed by rule ID prefix. The longest matching prefix wins, so "SG" beats "S".
+SECTIONS = {
+    "S": "Safety Rules",
+    "G": "General Game Rules",
+    "SG": "Specific Game Rules",
+    "SC": "Scoring Rules",
+    "R": "Robot Rules",
+    "T": "Tournament Rules",
+    "RSC": "Robot Skills Challenge Rules",
+    "VUG": "VEX U Game Rules",
+    "VUR": "VEX U Robot Rules",
+    "VUT": "VEX U Tournament Rules",
+    "VAIG": "VEX AI Game Rules",
+    "VAIR": "VEX AI Robot Rules",
+    "VAIT": "VEX AI Tournament Rules",
+}
+
+SECTION_HEADINGS = {heading.lower() for heading in SECTIONS.values()}
+
+
+def section_for(rule_id):
+    prefix = re.match(r"[A-Z]+", rule_id).group(0)
+    while prefix:
+        if prefix in SECTIONS:
+            return SECTIONS[prefix]
+        prefix = prefix[:-1]
+    return None
+
+
+def collapse(lines):
+    """Joins wrapped lines into paragraphs, dropping the layout's extra whitespace."""
+    paragraphs = []
+    current = []
+    for line in lines:
+        line = " ".join(line.split())
+        if line:
+            current.append(line)
+        elif current:
+            paragraphs.append(" ".join(current))
+            current = []
+    if current:
+        paragraphs.append(" ".join(current))
+
+    # Hyphenated words split across lines ("expan- sion") are rejoined.
+    return "\n\n".join(re.sub(r"(\w)- (\w)", r"\1\2", paragraph) for paragraph in paragraphs)
+
+
+def parse_rules(text):
+    rules = []
+    seen = set()
+    current = None
+
+    def finish():
+        if current is None:
+            return
+        rule = {
+            "id": current["id"],
+            "title": current["title"],
+            "section": section_for(current["id"]),
+            "text": collapse(current["text"]),
+            "page": current["page"],
+        }
+        if current["violation"]:
+            rule["violation"] = collapse(current["violation"])
+
+        # The manual repeats rule IDs in cross-references and its rule summary, so only the first full
+        # definition of each rule is kept.
+        if rule["id"] not in seen and rule["text"]:
+            seen.add(rule["id"])
+            rules.append(rule)
+
+    for page_number, page in enumerate(text.split("\f"), start=1):
+        for line in page.splitlines():
+            if PAGE_FOOTER.match(line):
+                continue
+
+            start = RULE_START.match(line)
+            if start:
+                finish()
+                current = {
+                    "id": start.group(1),
+                    "title": start.group(2).strip(),
+                    "text": [start.group(3) or ""],
+                    "violation": [],
+                    "page": page_number,
+                }
+                continue
+
+            if current is None:
+                continue
+
+            if line.strip().lower() in SECTION_HEADINGS:
+                finish()
+                current = None
+                continue
+
+            violation = VIOLATION_START.match(line)
+            if violation:
+                current["violation"].append(violation.group(1))
+            elif current["violation"]:
+                current["violation"].append(line)
+            else:
+                current["text"].append(line)
+
+    finish()
+    return rules
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("manual", help="text of the game manual, as produced by `pdftotext -layout`")
+    parser.add_argument("--season", required=True, help='season years, e.g. "2024-2025"')
+    parser.add_argument("--game", required=True, help="name of the season's game")
+    parser.add_argument("--version", required=True, help="version of the manual being imported")
+    parser.add_argument("--manual-url", required=True, help="link to the manual PDF")
+    args = parser.parse_args()
+
+    with open(args.manual, encoding="utf-8") as manual:
+        rules = parse_rules(manual.read())
+
+    if not rules:
+        sys.exit(f"No rules found in {args.manual}. Was it converted with `pdftotext -layout`?")
+
+    json.dump(
+        {
+            "season": args.season,
+            "game": args.game,
+            "version": args.version,
+            "manual_url": args.manual_url,
+            "rules": rules,
+        },
+        sys.stdout,
+        indent=4,
+        ensure_ascii=False,
+    )
+    sys.stdout.write("\n")
+    print(f"Imported {len(rules)} rules.", file=sys.stderr)
+
+
+if __name__ == "__main__":
+    main()
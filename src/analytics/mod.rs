@@ -0,0 +1,30 @@
+use robotevents::schema::MatchRound;
+
+pub mod opr;
+pub mod season_ratings;
+pub mod simulation;
+pub mod trueskill;
+
+pub use opr::*;
+pub use season_ratings::*;
+pub use simulation::*;
+pub use trueskill::*;
+
+/// RobotEvents round number for VIQC Teamwork finals, which [`MatchRound`] doesn't have a variant for.
+pub const TOP_N_ROUND: i32 = 15;
+
+/// Converts a match's RobotEvents round number into a [`MatchRound`].
+///
+/// Returns `None` for rounds that [`MatchRound`] doesn't cover, such as [`TOP_N_ROUND`].
+pub fn match_round(round: i32) -> Option<MatchRound> {
+    [
+        MatchRound::Practice,
+        MatchRound::Qualification,
+        MatchRound::Quarterfinals,
+        MatchRound::Semifinals,
+        MatchRound::Finals,
+        MatchRound::RoundOf16,
+    ]
+    .into_iter()
+    .find(|match_round| match_round.clone() as i32 == round)
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use robotevents::{
+    RobotEvents,
+    query::{DivisionMatchesQuery, PaginatedQuery},
+    schema::{Event, Match, MatchRound},
+};
+
+/// Small value added to the diagonal of each normal matrix before solving.
+///
+/// Teams that always play on the same alliance (or that have only played a single match) make the
+/// system singular, so this nudges their ratings towards splitting the score evenly instead.
+const RIDGE: f64 = 1e-6;
+
+/// Least-squares match statistics for a single team.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct MatchStatistics {
+    /// Offensive Power Rating: the team's estimated contribution to its alliance's score.
+    pub opr: f64,
+
+    /// Defensive Power Rating: the team's estimated contribution to its opponents' score.
+    ///
+    /// > This is `None` for programs where matches don't have an opposing alliance (such as VIQC).
+    pub dpr: Option<f64>,
+
+    /// Calculated Contribution to Winning Margin, equal to OPR minus DPR.
+    ///
+    /// > This is `None` for programs where matches don't have an opposing alliance (such as VIQC).
+    pub ccwm: Option<f64>,
+
+    /// Number of scored matches that this team's statistics were computed from.
+    pub matches_played: usize,
+}
+
+/// A single alliance's appearance in a scored match, used as one row of the least-squares system.
+struct AllianceRow {
+    teams: Vec<usize>,
+    score: f64,
+    opposing_score: Option<f64>,
+}
+
+/// Computes OPR, DPR and CCWM for every team that appears in a set of matches, keyed by RobotEvents
+/// team ID.
+///
+/// The set of matches can come from a single event, or from every event in a season. Only scored
+/// qualification matches are used, since elimination alliances aren't randomized, and teams sitting out
+/// of a match are ignored.
+pub fn match_statistics<'a>(matches: impl IntoIterator<Item = &'a Match>) -> HashMap<i32, MatchStatistics> {
+    let mut team_ids: Vec<i32> = Vec::new();
+    let mut team_indices: HashMap<i32, usize> = HashMap::new();
+    let mut rows: Vec<AllianceRow> = Vec::new();
+
+    for robotevents_match in matches {
+        if !robotevents_match.scored || robotevents_match.round != MatchRound::Qualification as i32 {
+            continue;
+        }
+
+        for (idx, alliance) in robotevents_match.alliances.iter().enumerate() {
+            let teams = alliance.teams
+                .iter()
+                .filter(|t| !t.sitting)
+                .map(|t| {
+                    *team_indices.entry(t.team.id).or_insert_with(|| {
+                        team_ids.push(t.team.id);
+                        team_ids.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            if teams.is_empty() {
+                continue;
+            }
+
+            let opposing_score = robotevents_match.alliances
+                .iter()
+                .enumerate()
+                .find(|(opposing_idx, _)| *opposing_idx != idx)
+                .map(|(_, opposing)| opposing.score as f64);
+
+            rows.push(AllianceRow {
+                teams,
+                score: alliance.score as f64,
+                opposing_score,
+            });
+        }
+    }
+
+    let mut matches_played = vec![0; team_ids.len()];
+    for row in rows.iter() {
+        for &i in row.teams.iter() {
+            matches_played[i] += 1;
+        }
+    }
+
+    let Some((oprs, dprs)) = power_ratings(&rows, team_ids.len()) else {
+        return HashMap::new();
+    };
+
+    team_ids
+        .iter()
+        .enumerate()
+        .map(|(idx, team_id)| {
+            let dpr = dprs.as_ref().map(|dprs| dprs[idx]);
+
+            (*team_id, MatchStatistics {
+                opr: oprs[idx],
+                dpr,
+                ccwm: dpr.map(|dpr| oprs[idx] - dpr),
+                matches_played: matches_played[idx],
+            })
+        })
+        .collect()
+}
+
+/// Solves for the OPR and DPR of every team index that appears in a set of alliance rows.
+///
+/// DPRs are `None` if none of the rows have an opposing alliance. Returns `None` if there are no teams,
+/// or if the offensive system can't be solved.
+fn power_ratings(rows: &[AllianceRow], team_count: usize) -> Option<(Vec<f64>, Option<Vec<f64>>)> {
+    if team_count == 0 {
+        return None;
+    }
+
+    // Build the normal equations (AᵀA)x = Aᵀb for both the offensive and defensive systems. Rows
+    // without an opposing alliance only contribute to the offensive system.
+    let mut offense_matrix = vec![vec![0.0; team_count]; team_count];
+    let mut defense_matrix = vec![vec![0.0; team_count]; team_count];
+    let mut offense_vector = vec![0.0; team_count];
+    let mut defense_vector = vec![0.0; team_count];
+    let mut has_opponents = false;
+
+    for row in rows.iter() {
+        for &i in row.teams.iter() {
+            offense_vector[i] += row.score;
+            for &j in row.teams.iter() {
+                offense_matrix[i][j] += 1.0;
+            }
+
+            if let Some(opposing_score) = row.opposing_score {
+                has_opponents = true;
+                defense_vector[i] += opposing_score;
+                for &j in row.teams.iter() {
+                    defense_matrix[i][j] += 1.0;
+                }
+            }
+        }
+    }
+
+    let oprs = solve_linear_system(offense_matrix, offense_vector)?;
+    let dprs = if has_opponents {
+        solve_linear_system(defense_matrix, defense_vector)
+    } else {
+        None
+    };
+
+    Some((oprs, dprs))
+}
+
+/// Solves a square linear system using Gaussian elimination with partial pivoting.
+///
+/// Returns `None` if the system is singular even after regularization.
+fn solve_linear_system(mut matrix: Vec<Vec<f64>>, mut vector: Vec<f64>) -> Option<Vec<f64>> {
+    let n = vector.len();
+
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[i] += RIDGE;
+    }
+
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < f64::EPSILON {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        vector.swap(col, pivot);
+
+        let pivot_row = matrix[col].clone();
+        for row in (col + 1)..n {
+            let factor = matrix[row][col] / pivot_row[col];
+            if factor == 0.0 {
+                continue;
+            }
+            for (value, pivot_value) in matrix[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *value -= factor * pivot_value;
+            }
+            vector[row] -= factor * vector[col];
+        }
+    }
+
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = ((row + 1)..n).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (vector[row] - sum) / matrix[row][row];
+    }
+
+    Some(solution)
+}
+
+/// Fetches every match from a single division of an event.
+pub async fn division_matches(robotevents: &RobotEvents, event: &Event, division_id: i32) -> Result<Vec<Match>, reqwest::Error> {
+    let mut matches = Vec::new();
+
+    let mut page = 1;
+    loop {
+        let response = event
+            .division_matches(division_id, robotevents, DivisionMatchesQuery::new().per_page(250).page(page))
+            .await?;
+
+        matches.extend(response.data);
+
+        if response.meta.current_page >= response.meta.last_page {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(matches)
+}
+
+/// Fetches every match from every division of an event.
+pub async fn event_matches(robotevents: &RobotEvents, event: &Event) -> Result<Vec<Match>, reqwest::Error> {
+    let mut matches = Vec::new();
+
+    for division in event.divisions.iter() {
+        matches.extend(division_matches(robotevents, event, division.id).await?);
+    }
+
+    Ok(matches)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-4, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn solves_three_team_ratings() {
+        // A + B = 30, B + C = 50 and A + C = 40, so A = 10, B = 20 and C = 30. The opposing scores
+        // work out the same way to DPRs of 20, 30 and 10.
+        let rows = vec![
+            AllianceRow { teams: vec![0, 1], score: 30.0, opposing_score: Some(50.0) },
+            AllianceRow { teams: vec![1, 2], score: 50.0, opposing_score: Some(40.0) },
+            AllianceRow { teams: vec![0, 2], score: 40.0, opposing_score: Some(30.0) },
+        ];
+
+        let (oprs, dprs) = power_ratings(&rows, 3).unwrap();
+        let dprs = dprs.unwrap();
+
+        for (actual, expected) in oprs.iter().zip([10.0, 20.0, 30.0]) {
+            assert_close(*actual, expected);
+        }
+        for (actual, expected) in dprs.iter().zip([20.0, 30.0, 10.0]) {
+            assert_close(*actual, expected);
+        }
+    }
+
+    #[test]
+    fn skips_dpr_without_opponents() {
+        let rows = vec![
+            AllianceRow { teams: vec![0, 1], score: 30.0, opposing_score: None },
+            AllianceRow { teams: vec![1, 2], score: 50.0, opposing_score: None },
+            AllianceRow { teams: vec![0, 2], score: 40.0, opposing_score: None },
+        ];
+
+        let (oprs, dprs) = power_ratings(&rows, 3).unwrap();
+
+        assert_close(oprs[2], 30.0);
+        assert_eq!(dprs, None);
+    }
+
+    #[test]
+    fn splits_scores_between_inseparable_teams() {
+        // Without regularization, two teams that only ever play together make the system singular.
+        let rows = vec![
+            AllianceRow { teams: vec![0, 1], score: 20.0, opposing_score: None },
+            AllianceRow { teams: vec![0, 1], score: 20.0, opposing_score: None },
+        ];
+
+        let (oprs, _) = power_ratings(&rows, 2).unwrap();
+
+        assert_close(oprs[0], 10.0);
+        assert_close(oprs[1], 10.0);
+    }
+
+    #[test]
+    fn pivots_around_zero_diagonal() {
+        // The first column's largest entry is in the last row, so rows have to be swapped to solve this.
+        let matrix = vec![
+            vec![0.0, 2.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+            vec![4.0, 0.0, 1.0],
+        ];
+        let solution = solve_linear_system(matrix, vec![7.0, 3.0, 7.0]).unwrap();
+
+        for (actual, expected) in solution.iter().zip([1.0, 2.0, 3.0]) {
+            assert_close(*actual, expected);
+        }
+    }
+
+    #[test]
+    fn returns_no_ratings_without_teams() {
+        assert_eq!(power_ratings(&[], 0), None);
+    }
+}
@@ -0,0 +1,102 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use robotevents::{
+    RobotEvents,
+    query::{PaginatedQuery, SeasonEventsQuery},
+    schema::Event,
+};
+use tokio::sync::RwLock;
+
+use crate::analytics::{event_matches, TrueSkillEngine};
+
+/// Season-wide TrueSkill ratings, keyed by RobotEvents season ID.
+///
+/// Rating a whole season means downloading the matches of every event in it, which takes far too long to
+/// do while a command waits. Seasons are instead rebuilt in the background by
+/// [`SeasonRatingsCache::spawn_refresh_task`], and commands fall back to rating only the events they need
+/// until a season's first build has finished.
+#[derive(Default, Debug, Clone)]
+pub struct SeasonRatingsCache {
+    entries: Arc<RwLock<HashMap<i32, Arc<TrueSkillEngine>>>>,
+}
+
+impl SeasonRatingsCache {
+    /// Returns the ratings for every team that played in a season, if the season has been built.
+    pub async fn get(&self, season_id: i32) -> Option<Arc<TrueSkillEngine>> {
+        self.entries.read().await.get(&season_id).cloned()
+    }
+
+    /// Fetches every event in a season that has already started.
+    async fn season_events(season_id: i32, robotevents: &RobotEvents) -> Result<Vec<Event>, reqwest::Error> {
+        let mut events = Vec::new();
+
+        let mut page = 1;
+        loop {
+            let response = robotevents
+                .season_events(season_id, SeasonEventsQuery::new().per_page(250).page(page))
+                .await?;
+
+            events.extend(response.data);
+
+            if response.meta.current_page >= response.meta.last_page {
+                break;
+            }
+            page += 1;
+        }
+
+        let now = Utc::now();
+        events.retain(|event| match DateTime::parse_from_rfc3339(&event.start) {
+            Ok(start) => start <= now,
+            Err(_) => true,
+        });
+
+        Ok(events)
+    }
+
+    /// Builds the ratings for a season by replaying every event in it, oldest first.
+    ///
+    /// Events whose matches can't be fetched are skipped, so that one bad event doesn't throw away the rest
+    /// of the season.
+    async fn build(season_id: i32, robotevents: &RobotEvents) -> Result<TrueSkillEngine, reqwest::Error> {
+        let mut events = Self::season_events(season_id, robotevents).await?;
+        events.sort_by(|a, b| a.start.cmp(&b.start));
+
+        let mut engine = TrueSkillEngine::default();
+
+        // Events are replayed one at a time as they're downloaded, rather than collecting the whole season's
+        // matches first. Replaying a single event is cheap next to the requests needed to fetch it.
+        for event in events.iter() {
+            match event_matches(robotevents, event).await {
+                Ok(matches) => engine.replay(&matches),
+                Err(err) => println!("Failed to fetch matches for {} while building season ratings: {err}", event.sku),
+            }
+        }
+
+        Ok(engine)
+    }
+
+    /// Spawns a task that builds the ratings for each of `season_ids` right away, then rebuilds them every
+    /// `interval`.
+    ///
+    /// Seasons are built one after another to go easy on the RobotEvents API. The previous build of a season
+    /// keeps being served until its replacement is finished.
+    pub fn spawn_refresh_task(&self, robotevents: RobotEvents, season_ids: Vec<i32>, interval: Duration) {
+        let cache = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                for season_id in season_ids.iter() {
+                    match Self::build(*season_id, &robotevents).await {
+                        Ok(engine) => {
+                            cache.entries.write().await.insert(*season_id, Arc::new(engine));
+                        },
+                        Err(err) => println!("Failed to build TrueSkill ratings for season {season_id}: {err}"),
+                    }
+                }
+
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use robotevents::schema::{Match, MatchRound};
+
+use crate::analytics::TrueSkillEngine;
+
+/// A team's projected final qualification ranking, summarized over every simulated outcome.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct RankingProjection {
+    /// Team number.
+    pub team: String,
+
+    /// Number of times the team finished at each rank, where index 0 is rank 1.
+    pub rank_counts: Vec<usize>,
+
+    /// Average final rank across all simulations.
+    pub average_rank: f64,
+
+    /// Probability (from 0.0 to 1.0) of finishing in the top 8.
+    pub top_8: f64,
+
+    /// Probability (from 0.0 to 1.0) of finishing in the top 16.
+    pub top_16: f64,
+}
+
+impl RankingProjection {
+    /// Returns the rank that the team finishes at or better in at least `fraction` of simulations.
+    pub fn rank_percentile(&self, fraction: f64) -> usize {
+        let total = self.rank_counts.iter().sum::<usize>() as f64;
+        let mut cumulative = 0;
+
+        for (idx, count) in self.rank_counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative as f64 >= total * fraction {
+                return idx + 1;
+            }
+        }
+
+        self.rank_counts.len()
+    }
+}
+
+/// Projected rankings for every team in a division.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct DivisionProjection {
+    /// Each team's projection, ordered by average projected rank.
+    pub teams: Vec<RankingProjection>,
+
+    /// Number of qualification matches that were still unplayed and had to be simulated.
+    pub remaining_matches: usize,
+
+    /// Number of times the remaining matches were simulated.
+    pub iterations: usize,
+}
+
+/// Standings accumulated by a single team over a set of qualification matches.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+struct Standing {
+    wp: i32,
+    ap: i32,
+    sp: i32,
+    high_score: i32,
+}
+
+impl Standing {
+    /// Adds the result of a single match to the standing.
+    fn add_match(&mut self, wp: i32, sp: i32, score: i32) {
+        self.wp += wp;
+        self.sp += sp;
+        self.high_score = self.high_score.max(score);
+    }
+}
+
+/// An unplayed match, with its predicted result from the red alliance's perspective.
+struct UnplayedMatch {
+    red: Vec<String>,
+    blue: Vec<String>,
+    red_win_probability: f64,
+    red_score: i32,
+    blue_score: i32,
+}
+
+/// Simulates the remaining qualification matches of a division many times, and summarizes where each
+/// team ends up in the rankings.
+///
+/// Completed matches are counted as-is, and each unplayed match is decided randomly using win
+/// probabilities from `ratings`. The alliances in a simulated match are given the average score of
+/// their teams' completed matches, with the winner taking the higher of the two scores. Autonomous
+/// points can't be simulated, so each team keeps the AP from `autonomous_points` (usually taken from the
+/// division's current rankings).
+///
+/// Teams are ranked the way qualification rankings are: by win points (2 for a win, 1 for a tie), then
+/// autonomous points, then strength of schedule points (the losing alliance's score), then their
+/// highest match score, with any remaining ties broken randomly.
+///
+/// Returns `None` if the matches don't include any two-alliance qualification matches.
+pub fn project_rankings<'a>(
+    matches: impl IntoIterator<Item = &'a Match>,
+    ratings: &TrueSkillEngine,
+    autonomous_points: &HashMap<String, i32>,
+    iterations: usize,
+) -> Option<DivisionProjection> {
+    let mut base_standings: HashMap<String, Standing> = HashMap::new();
+    let mut scores: HashMap<String, (i32, i32)> = HashMap::new();
+    let mut unplayed_matches = Vec::new();
+
+    for robotevents_match in matches {
+        if robotevents_match.round != MatchRound::Qualification as i32 {
+            continue;
+        }
+
+        let [red, blue] = robotevents_match.alliances.as_slice() else {
+            continue;
+        };
+        let team_numbers = |alliance: &robotevents::schema::Alliance| {
+            alliance.teams
+                .iter()
+                .filter(|t| !t.sitting)
+                .map(|t| t.team.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let (red_teams, blue_teams) = (team_numbers(red), team_numbers(blue));
+
+        for team in red_teams.iter().chain(blue_teams.iter()) {
+            base_standings.entry(team.clone()).or_insert(Standing {
+                ap: autonomous_points.get(team).copied().unwrap_or_default(),
+                ..Default::default()
+            });
+        }
+
+        if robotevents_match.scored {
+            let (red_wp, blue_wp) = match red.score.cmp(&blue.score) {
+                std::cmp::Ordering::Greater => (2, 0),
+                std::cmp::Ordering::Less => (0, 2),
+                std::cmp::Ordering::Equal => (1, 1),
+            };
+            let sp = red.score.min(blue.score);
+
+            for (teams, wp, score) in [(&red_teams, red_wp, red.score), (&blue_teams, blue_wp, blue.score)] {
+                for team in teams.iter() {
+                    base_standings.get_mut(team).unwrap().add_match(wp, sp, score);
+
+                    let (score_sum, played) = scores.entry(team.clone()).or_default();
+                    *score_sum += score;
+                    *played += 1;
+                }
+            }
+        } else {
+            let red_refs = red_teams.iter().map(String::as_str).collect::<Vec<_>>();
+            let blue_refs = blue_teams.iter().map(String::as_str).collect::<Vec<_>>();
+
+            unplayed_matches.push(UnplayedMatch {
+                red_win_probability: ratings.win_probability(&red_refs, &blue_refs),
+                red: red_teams,
+                blue: blue_teams,
+                red_score: 0,
+                blue_score: 0,
+            });
+        }
+    }
+
+    if base_standings.is_empty() {
+        return None;
+    }
+
+    // Teams without any completed matches are assumed to score the division's average.
+    let (total_score, total_played) = scores
+        .values()
+        .fold((0, 0), |(score_sum, played), team| (score_sum + team.0, played + team.1));
+    let division_average = if total_played > 0 { total_score as f64 / total_played as f64 } else { 0.0 };
+    let average_score = |team: &String| match scores.get(team) {
+        Some((score_sum, played)) => *score_sum as f64 / *played as f64,
+        None => division_average,
+    };
+    let alliance_score = |teams: &[String]| {
+        if teams.is_empty() {
+            0
+        } else {
+            (teams.iter().map(average_score).sum::<f64>() / teams.len() as f64).round() as i32
+        }
+    };
+
+    for unplayed_match in unplayed_matches.iter_mut() {
+        unplayed_match.red_score = alliance_score(&unplayed_match.red);
+        unplayed_match.blue_score = alliance_score(&unplayed_match.blue);
+    }
+
+    let teams = base_standings.keys().cloned().collect::<Vec<_>>();
+    let team_count = teams.len();
+    let mut rank_counts: HashMap<&str, Vec<usize>> = teams
+        .iter()
+        .map(|team| (team.as_str(), vec![0; team_count]))
+        .collect();
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..iterations {
+        let mut standings = base_standings.clone();
+
+        for unplayed_match in unplayed_matches.iter() {
+            let (winners, losers) = if rng.gen_bool(unplayed_match.red_win_probability.clamp(0.0, 1.0)) {
+                (&unplayed_match.red, &unplayed_match.blue)
+            } else {
+                (&unplayed_match.blue, &unplayed_match.red)
+            };
+            let winning_score = unplayed_match.red_score.max(unplayed_match.blue_score);
+            let losing_score = unplayed_match.red_score.min(unplayed_match.blue_score);
+
+            for team in winners.iter() {
+                standings.get_mut(team).unwrap().add_match(2, losing_score, winning_score);
+            }
+            for team in losers.iter() {
+                standings.get_mut(team).unwrap().add_match(0, losing_score, losing_score);
+            }
+        }
+
+        let mut order = teams
+            .iter()
+            .map(|team| (team.as_str(), standings[team], rng.gen::<u32>()))
+            .collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            (b.1.wp, b.1.ap, b.1.sp, b.1.high_score, b.2).cmp(&(a.1.wp, a.1.ap, a.1.sp, a.1.high_score, a.2))
+        });
+
+        for (rank, (team, _, _)) in order.iter().enumerate() {
+            rank_counts.get_mut(team).unwrap()[rank] += 1;
+        }
+    }
+
+    let mut projections = rank_counts
+        .into_iter()
+        .map(|(team, counts)| {
+            let total = iterations.max(1) as f64;
+            let rank_sum = counts.iter().enumerate().map(|(idx, count)| (idx + 1) * count).sum::<usize>();
+
+            RankingProjection {
+                team: team.to_string(),
+                average_rank: rank_sum as f64 / total,
+                top_8: counts.iter().take(8).sum::<usize>() as f64 / total,
+                top_16: counts.iter().take(16).sum::<usize>() as f64 / total,
+                rank_counts: counts,
+            }
+        })
+        .collect::<Vec<_>>();
+    projections.sort_by(|a, b| a.average_rank.total_cmp(&b.average_rank));
+
+    Some(DivisionProjection {
+        teams: projections,
+        remaining_matches: unplayed_matches.len(),
+        iterations,
+    })
+}
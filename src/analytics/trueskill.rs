@@ -0,0 +1,458 @@
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::{PI, SQRT_2};
+use std::fmt;
+
+use robotevents::{
+    RobotEvents,
+    schema::{Event, Match, MatchRound},
+};
+
+use crate::analytics::event_matches;
+
+/// Errors that can occur while building a rating engine from RobotEvents match results.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// Match results couldn't be fetched from RobotEvents.
+    Request(reqwest::Error),
+
+    /// The blocking task replaying the matches panicked or was cancelled.
+    Task(tokio::task::JoinError),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request(err) => write!(f, "request error: {err}"),
+            Self::Task(err) => write!(f, "replay task failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<reqwest::Error> for ReplayError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Request(err)
+    }
+}
+
+impl From<tokio::task::JoinError> for ReplayError {
+    fn from(err: tokio::task::JoinError) -> Self {
+        Self::Task(err)
+    }
+}
+
+/// Tuning parameters for the TrueSkill model.
+///
+/// The defaults match those from the original TrueSkill paper, scaled so that a new team starts
+/// with a mean skill of 25.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrueSkillConfig {
+    /// Initial mean skill of a team that hasn't played any matches.
+    pub mu: f64,
+
+    /// Initial skill uncertainty of a team that hasn't played any matches.
+    pub sigma: f64,
+
+    /// Distance in skill that gives a stronger team roughly a 76% chance of winning.
+    pub beta: f64,
+
+    /// Additive dynamics factor, which keeps a team's uncertainty from shrinking to nothing over a season.
+    pub tau: f64,
+
+    /// Probability that a match between two evenly-matched alliances ends in a tie.
+    pub draw_probability: f64,
+}
+
+impl Default for TrueSkillConfig {
+    fn default() -> Self {
+        Self {
+            mu: 25.0,
+            sigma: 25.0 / 3.0,
+            beta: 25.0 / 6.0,
+            tau: 25.0 / 300.0,
+            draw_probability: 0.01,
+        }
+    }
+}
+
+/// A single team's skill estimate, modelled as a normal distribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    pub mu: f64,
+    pub sigma: f64,
+
+    /// Number of matches that have been used to update this rating.
+    pub matches_played: usize,
+}
+
+impl Rating {
+    /// A conservative estimate of the team's skill (μ - 3σ), which is what rankings are ordered by.
+    pub fn skill(&self) -> f64 {
+        self.mu - 3.0 * self.sigma
+    }
+}
+
+/// In-process TrueSkill rating engine.
+///
+/// Ratings are built up by replaying RobotEvents match results in chronological order, and are keyed
+/// by team number so that they can be looked up with the same input as vrc-data-analysis.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct TrueSkillEngine {
+    config: TrueSkillConfig,
+    ratings: HashMap<String, Rating>,
+}
+
+impl TrueSkillEngine {
+    /// Returns the rating given to a team that hasn't played any matches.
+    pub fn default_rating(&self) -> Rating {
+        Rating {
+            mu: self.config.mu,
+            sigma: self.config.sigma,
+            matches_played: 0,
+        }
+    }
+
+    /// Returns the current rating of a team, or the default rating if the team hasn't played yet.
+    pub fn rating(&self, team_number: &str) -> Rating {
+        self.ratings.get(team_number).copied().unwrap_or(self.default_rating())
+    }
+
+    /// Overrides the rating of a team, such as when seeding the engine with ratings from vrc-data-analysis.
+    pub fn set_rating(&mut self, team_number: impl Into<String>, rating: Rating) {
+        self.ratings.insert(team_number.into(), rating);
+    }
+
+    /// Returns every rated team ordered from highest to lowest skill, along with its rating. Teams with the
+    /// same skill are ordered by team number.
+    pub fn rankings(&self) -> Vec<(String, Rating)> {
+        let mut rankings = self.ratings
+            .iter()
+            .map(|(number, rating)| (number.clone(), *rating))
+            .collect::<Vec<_>>();
+        rankings.sort_by(|a, b| b.1.skill().total_cmp(&a.1.skill()).then_with(|| a.0.cmp(&b.0)));
+
+        rankings
+    }
+
+    /// Returns a team's position in [`Self::rankings`], starting from 1.
+    pub fn ranking(&self, team_number: &str) -> Option<usize> {
+        self.rankings()
+            .iter()
+            .position(|(number, _)| number == team_number)
+            .map(|idx| idx + 1)
+    }
+
+    /// Returns the probability (from 0.0 to 1.0) that the red alliance beats the blue alliance.
+    pub fn win_probability(&self, red_alliance: &[&str], blue_alliance: &[&str]) -> f64 {
+        let red = red_alliance.iter().map(|number| self.rating(number)).collect::<Vec<_>>();
+        let blue = blue_alliance.iter().map(|number| self.rating(number)).collect::<Vec<_>>();
+
+        let mu_difference = red.iter().map(|r| r.mu).sum::<f64>() - blue.iter().map(|r| r.mu).sum::<f64>();
+        let variance = red.iter().chain(blue.iter()).map(|r| r.sigma.powi(2) + self.config.beta.powi(2)).sum::<f64>();
+
+        normal_cdf(mu_difference / variance.sqrt())
+    }
+
+    /// Replays a set of matches, updating the rating of every team that played in them.
+    ///
+    /// Matches are sorted by start time before being replayed. Practice and unscored matches are
+    /// ignored, as are teams sitting out of a match.
+    ///
+    /// Cooperative matches with a single alliance (such as VIQC Teamwork Challenge matches) are
+    /// treated as a match against an average alliance, which the alliance beats if it scored higher
+    /// than the average cooperative score in the set of matches.
+    pub fn replay<'a>(&mut self, matches: impl IntoIterator<Item = &'a Match>) {
+        let mut matches = matches
+            .into_iter()
+            .filter(|m| m.scored && m.round != MatchRound::Practice as i32)
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| {
+            a.started.as_deref().or(a.scheduled.as_deref())
+                .cmp(&b.started.as_deref().or(b.scheduled.as_deref()))
+        });
+
+        let cooperative_scores = matches
+            .iter()
+            .filter(|m| m.alliances.len() == 1)
+            .map(|m| m.alliances[0].score as f64)
+            .collect::<Vec<_>>();
+        let average_cooperative_score = if cooperative_scores.is_empty() {
+            0.0
+        } else {
+            cooperative_scores.iter().sum::<f64>() / cooperative_scores.len() as f64
+        };
+
+        for robotevents_match in matches {
+            let alliances = robotevents_match.alliances
+                .iter()
+                .map(|alliance| {
+                    (
+                        alliance.teams
+                            .iter()
+                            .filter(|t| !t.sitting)
+                            .map(|t| t.team.name.clone())
+                            .collect::<Vec<_>>(),
+                        alliance.score as f64,
+                    )
+                })
+                .filter(|(teams, _)| !teams.is_empty())
+                .collect::<Vec<_>>();
+
+            match alliances.as_slice() {
+                [(red, red_score), (blue, blue_score)] => {
+                    self.update(red, blue, red_score.total_cmp(blue_score));
+                },
+                [(alliance, score)] => {
+                    self.update_against_average(alliance, score.total_cmp(&average_cooperative_score));
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Updates the ratings of two alliances after a match.
+    ///
+    /// `outcome` is the result from the red alliance's perspective.
+    pub fn update(&mut self, red_alliance: &[String], blue_alliance: &[String], outcome: std::cmp::Ordering) {
+        let red = self.prepare_alliance(red_alliance);
+        let blue = self.prepare_alliance(blue_alliance);
+
+        self.apply_update(red_alliance, &red, &blue, outcome);
+        self.apply_update(blue_alliance, &blue, &red, outcome.reverse());
+    }
+
+    /// Updates the ratings of a single alliance after a match against an alliance of default-rated teams.
+    fn update_against_average(&mut self, alliance: &[String], outcome: std::cmp::Ordering) {
+        let ratings = self.prepare_alliance(alliance);
+        let average = vec![self.default_rating(); alliance.len()];
+
+        self.apply_update(alliance, &ratings, &average, outcome);
+    }
+
+    /// Returns the ratings of an alliance's teams with the dynamics factor applied.
+    fn prepare_alliance(&self, alliance: &[String]) -> Vec<Rating> {
+        alliance
+            .iter()
+            .map(|number| {
+                let rating = self.rating(number);
+                Rating {
+                    sigma: (rating.sigma.powi(2) + self.config.tau.powi(2)).sqrt(),
+                    ..rating
+                }
+            })
+            .collect()
+    }
+
+    /// Applies the TrueSkill update for a two-alliance match to the teams of a single alliance.
+    fn apply_update(&mut self, team_numbers: &[String], alliance: &[Rating], opposing: &[Rating], outcome: std::cmp::Ordering) {
+        let player_count = (alliance.len() + opposing.len()) as f64;
+        let c = alliance
+            .iter()
+            .chain(opposing.iter())
+            .map(|r| r.sigma.powi(2) + self.config.beta.powi(2))
+            .sum::<f64>()
+            .sqrt();
+
+        let draw_margin = normal_ppf((self.config.draw_probability + 1.0) / 2.0) * player_count.sqrt() * self.config.beta;
+        let t = (alliance.iter().map(|r| r.mu).sum::<f64>() - opposing.iter().map(|r| r.mu).sum::<f64>()) / c;
+        let epsilon = draw_margin / c;
+
+        let (v, w) = match outcome {
+            std::cmp::Ordering::Greater => v_w_win(t, epsilon),
+            std::cmp::Ordering::Less => {
+                let (v, w) = v_w_win(-t, epsilon);
+                (-v, w)
+            },
+            std::cmp::Ordering::Equal => v_w_draw(t, epsilon),
+        };
+
+        for (number, rating) in team_numbers.iter().zip(alliance.iter()) {
+            let variance = rating.sigma.powi(2);
+
+            self.ratings.insert(number.clone(), Rating {
+                mu: rating.mu + variance / c * v,
+                sigma: (variance * (1.0 - variance / c.powi(2) * w).max(f64::EPSILON)).sqrt(),
+                matches_played: rating.matches_played + 1,
+            });
+        }
+    }
+}
+
+/// Mean and variance multipliers for a win, where `t` is the normalized skill difference in favor of
+/// the winner.
+fn v_w_win(t: f64, epsilon: f64) -> (f64, f64) {
+    let x = t - epsilon;
+    let denominator = normal_cdf(x);
+
+    // Guard against underflow when the winner was overwhelmingly expected to lose.
+    let v = if denominator < f64::MIN_POSITIVE { -x } else { normal_pdf(x) / denominator };
+
+    (v, v * (v + x))
+}
+
+/// Mean and variance multipliers for a tie, where `t` is the normalized skill difference.
+fn v_w_draw(t: f64, epsilon: f64) -> (f64, f64) {
+    let denominator = normal_cdf(epsilon - t) - normal_cdf(-epsilon - t);
+    if denominator < f64::MIN_POSITIVE {
+        return (if t < 0.0 { -t - epsilon } else { -t + epsilon }, 1.0);
+    }
+
+    let v = (normal_pdf(-epsilon - t) - normal_pdf(epsilon - t)) / denominator;
+    let w = v.powi(2) + ((epsilon - t) * normal_pdf(epsilon - t) + (epsilon + t) * normal_pdf(epsilon + t)) / denominator;
+
+    (v, w)
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x.powi(2) / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    erfc(-x / SQRT_2) / 2.0
+}
+
+/// Complementary error function, using the Chebyshev approximation from Numerical Recipes
+/// (accurate to about 1.2e-7).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + z / 2.0);
+    let r = t * (-z * z - 1.26551223
+        + t * (1.00002368
+        + t * (0.37409196
+        + t * (0.09678418
+        + t * (-0.18628806
+        + t * (0.27886807
+        + t * (-1.13520398
+        + t * (1.48851587
+        + t * (-0.82215223
+        + t * 0.17087277)))))))))
+        .exp();
+
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Inverse of the standard normal CDF, using Acklam's rational approximation.
+fn normal_ppf(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -normal_ppf(1.0 - p)
+    }
+}
+
+/// Builds a rating engine by replaying every match from a set of events.
+///
+/// Events that appear more than once in `events` are only replayed once.
+pub async fn replay_events<'a>(
+    robotevents: &RobotEvents,
+    events: impl IntoIterator<Item = &'a Event>,
+) -> Result<TrueSkillEngine, ReplayError> {
+    let mut replayed_events = HashSet::new();
+    let mut matches = Vec::new();
+
+    for event in events {
+        if replayed_events.insert(event.id) {
+            matches.extend(event_matches(robotevents, event).await?);
+        }
+    }
+
+    // Replaying a season's worth of matches is CPU-bound, so it's kept off the async runtime.
+    let engine = tokio::task::spawn_blocking(move || {
+        let mut engine = TrueSkillEngine::default();
+        engine.replay(&matches);
+        engine
+    })
+    .await?;
+
+    Ok(engine)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn erfc_matches_known_values() {
+        assert_close(erfc(0.0), 1.0, 1e-6);
+        assert_close(erfc(0.5), 0.4795001222, 1e-6);
+        assert_close(erfc(1.0), 0.1572992071, 1e-6);
+        assert_close(erfc(-1.0), 1.8427007929, 1e-6);
+        assert_close(erfc(2.0), 0.0046777350, 1e-6);
+    }
+
+    #[test]
+    fn normal_cdf_matches_known_values() {
+        assert_close(normal_cdf(0.0), 0.5, 1e-6);
+        assert_close(normal_cdf(1.96), 0.9750021049, 1e-6);
+        assert_close(normal_cdf(-1.0), 0.1586552539, 1e-6);
+    }
+
+    #[test]
+    fn normal_ppf_matches_known_values() {
+        assert_close(normal_ppf(0.5), 0.0, 1e-9);
+        assert_close(normal_ppf(0.975), 1.9599639845, 1e-6);
+        assert_close(normal_ppf(0.01), -2.3263478740, 1e-6);
+        assert_close(normal_ppf(0.99), 2.3263478740, 1e-6);
+    }
+
+    #[test]
+    fn normal_ppf_inverts_normal_cdf() {
+        for p in [0.001, 0.02, 0.1, 0.3, 0.7, 0.9, 0.98, 0.999] {
+            assert_close(normal_cdf(normal_ppf(p)), p, 1e-6);
+        }
+    }
+
+    #[test]
+    fn v_w_win_matches_known_values() {
+        // With no skill difference and no draw margin, v = φ(0) / Φ(0) = √(2/π) and w = v².
+        let (v, w) = v_w_win(0.0, 0.0);
+
+        assert_close(v, std::f64::consts::FRAC_2_PI.sqrt(), 1e-6);
+        assert_close(w, std::f64::consts::FRAC_2_PI, 1e-6);
+    }
+
+    #[test]
+    fn v_w_draw_is_neutral_between_equal_teams() {
+        let (v, w) = v_w_draw(0.0, 0.1);
+
+        assert_close(v, 0.0, 1e-9);
+        assert!(w > 0.0 && w < 1.0);
+    }
+
+    #[test]
+    fn winning_raises_rating() {
+        let mut engine = TrueSkillEngine::default();
+        let (red, blue) = (vec!["1A".to_string(), "2A".to_string()], vec!["3A".to_string(), "4A".to_string()]);
+
+        assert_close(engine.win_probability(&["1A", "2A"], &["3A", "4A"]), 0.5, 1e-6);
+
+        engine.update(&red, &blue, std::cmp::Ordering::Greater);
+
+        let (winner, loser) = (engine.rating("1A"), engine.rating("3A"));
+        assert!(winner.mu > 25.0);
+        assert!(loser.mu < 25.0);
+        assert!(winner.sigma < 25.0 / 3.0);
+        assert_eq!(winner.matches_played, 1);
+        assert!(engine.win_probability(&["1A", "2A"], &["3A", "4A"]) > 0.5);
+        assert_eq!(engine.ranking("1A"), Some(1));
+    }
+}
@@ -1,2 +1,3 @@
 pub mod vrc_data_analysis;
-pub mod skills;
\ No newline at end of file
+pub mod skills;
+pub mod skills_history;
\ No newline at end of file
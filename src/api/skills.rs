@@ -2,7 +2,19 @@ use std::{collections::HashMap, time::{Instant, Duration}, sync::Arc};
 
 use robotevents::RobotEvents;
 use serde::{Serialize, Deserialize};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::api::skills_history::SkillsHistory;
+use crate::lookup_index::{IndexedTeam, LookupIndex};
+
+/// How long a cached leaderboard is considered fresh for.
+///
+/// Past this, the stale leaderboard is still served while a new copy is fetched in the background.
+const CACHE_TTL: Duration = Duration::from_secs(43200);
+
+/// Leaderboards that have been requested within this window are considered "hot", and are kept up to
+/// date by [`SkillsCache::spawn_refresh_task`].
+const HOT_WINDOW: Duration = Duration::from_secs(86400);
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -55,36 +67,214 @@ pub struct SkillsRanking {
 	pub eligible: bool,
 }
 
+/// A team's entry on the world skills leaderboard, along with where it places among teams from the same
+/// region and country.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TeamSkillsRanking {
+	pub ranking: SkillsRanking,
+	pub region_rank: usize,
+	pub country_rank: usize,
+}
+
+/// Key for a cached leaderboard: season ID, grade level, and whether it's for post-season events.
+///
+/// Post-season events (such as Worlds) are ranked separately from the regular season, so they're
+/// cached as a separate leaderboard.
 #[derive(Default, Debug, Clone, Hash, PartialEq, Eq)]
-pub struct SkillsCacheKey(i32, String);
+pub struct SkillsCacheKey(i32, String, bool);
 
+/// A cached skills leaderboard, sorted by world rank.
+#[derive(Debug, Clone)]
+struct SkillsCacheEntry {
+	rankings: Arc<Vec<SkillsRanking>>,
+	fetched_at: Instant,
+	last_accessed: Instant,
+}
+
+/// Cache of RobotEvents skills leaderboards, keyed by season and grade level.
+///
+/// Leaderboards are large and slow to download, so the cache is built to never block one request on
+/// another's HTTP call:
+/// - The entry map is only ever locked briefly, and never across a request.
+/// - Each key has its own fetch lock, so concurrent requests for the same missing leaderboard share a
+///   single download.
+/// - Stale leaderboards are served immediately while being refreshed in the background.
+///
+/// If a [`SkillsHistory`] is attached, every downloaded leaderboard is also saved as a snapshot. If a
+/// [`LookupIndex`] is attached, every team on a downloaded leaderboard is added to it.
 #[derive(Default, Debug, Clone)]
 pub struct SkillsCache {
-	cache: Arc<Mutex<HashMap<SkillsCacheKey, (Vec<SkillsRanking>, std::time::Instant)>>>,
+	entries: Arc<RwLock<HashMap<SkillsCacheKey, SkillsCacheEntry>>>,
+	fetch_locks: Arc<Mutex<HashMap<SkillsCacheKey, Arc<Mutex<()>>>>>,
+	history: Option<SkillsHistory>,
+	lookup_index: Option<LookupIndex>,
 }
 
 impl SkillsCache {
-	pub async fn get_team_ranking(&self, team: &robotevents::schema::Team, season_id: i32, robotevents: &RobotEvents) -> Result<Option<SkillsRanking>, reqwest::Error> {
-		let mut cache = self.cache.lock().await;
-		let key = SkillsCacheKey(season_id, team.grade.to_string());
-		
-		let rankings = match cache.get(&key) {
-			Some((rankings, timestamp)) if timestamp.elapsed() < Duration::from_secs(43200) => rankings,
-			_ => {
-				let fetched_rankings: Vec<SkillsRanking> = robotevents
-					.request_api_v1(format!("/seasons/{season_id}/skills?grade_level={}&post_season=0", team.grade.to_string()))
-					.await?
-					.json()
-					.await?;
-
-				cache.insert(key.clone(), (fetched_rankings, Instant::now()));
-				&cache.get(&key).expect("Cache should be full after being immediately updated.").0
-			},
+	/// Creates a cache that records a snapshot to `history` every time a leaderboard is downloaded.
+	pub fn with_history(history: SkillsHistory) -> Self {
+		Self {
+			history: Some(history),
+			..Default::default()
+		}
+	}
+
+	/// Adds the teams from every leaderboard downloaded from now on to `lookup_index`.
+	pub fn set_lookup_index(&mut self, lookup_index: LookupIndex) {
+		self.lookup_index = Some(lookup_index);
+	}
+
+	/// Returns the snapshot history attached to this cache, if any.
+	pub fn history(&self) -> Option<&SkillsHistory> {
+		self.history.as_ref()
+	}
+
+	/// Returns the fetch lock for a key, creating it if it doesn't exist yet.
+	async fn fetch_lock(&self, key: &SkillsCacheKey) -> Arc<Mutex<()>> {
+		self.fetch_locks.lock().await.entry(key.clone()).or_default().clone()
+	}
+
+	/// Downloads a leaderboard from the RobotEvents v1 API and stores it in the cache.
+	async fn fetch(&self, key: &SkillsCacheKey, robotevents: &RobotEvents) -> Result<Arc<Vec<SkillsRanking>>, reqwest::Error> {
+		let SkillsCacheKey(season_id, grade, post_season) = key;
+
+		let mut fetched_rankings: Vec<SkillsRanking> = robotevents
+			.request_api_v1(format!("/seasons/{season_id}/skills?grade_level={grade}&post_season={}", *post_season as i32))
+			.await?
+			.json()
+			.await?;
+		fetched_rankings.sort_by_key(|ranking| ranking.rank);
+
+		let rankings = Arc::new(fetched_rankings);
+		{
+			let mut entries = self.entries.write().await;
+			let last_accessed = entries.get(key).map(|entry| entry.last_accessed).unwrap_or(Instant::now());
+			entries.insert(key.clone(), SkillsCacheEntry {
+				rankings: rankings.clone(),
+				fetched_at: Instant::now(),
+				last_accessed,
+			});
+		}
+
+		// A failed snapshot shouldn't keep the freshly downloaded leaderboard from being served.
+		if let Some(history) = &self.history {
+			if let Err(err) = history.record(*season_id, grade, *post_season, &rankings).await {
+				println!("Failed to save skills leaderboard snapshot for {key:?}: {err}");
+			}
+		}
+
+		if let Some(lookup_index) = &self.lookup_index {
+			lookup_index.add_teams(rankings.iter().map(|ranking| IndexedTeam {
+				number: ranking.team.team.clone(),
+				name: ranking.team.team_name.clone(),
+				program: ranking.team.program.clone(),
+			})).await;
+		}
+
+		Ok(rankings)
+	}
+
+	/// Refreshes a leaderboard in the background, unless a fetch for it is already in progress.
+	fn spawn_refresh(&self, key: SkillsCacheKey, robotevents: &RobotEvents) {
+		let cache = self.clone();
+		let robotevents = robotevents.clone();
+
+		tokio::spawn(async move {
+			let lock = cache.fetch_lock(&key).await;
+			let Ok(_guard) = lock.try_lock() else {
+				return;
+			};
+
+			if let Err(err) = cache.fetch(&key, &robotevents).await {
+				println!("Failed to refresh skills leaderboard for {key:?}: {err}");
+			}
+		});
+	}
+
+	/// Returns the full skills leaderboard for a season, grade level and season type, sorted by world rank.
+	///
+	/// Leaderboards are fetched from the RobotEvents v1 API if they aren't cached yet. Cached leaderboards
+	/// more than 12 hours old are returned as-is, and refreshed in the background.
+	pub async fn get_rankings(&self, season_id: i32, grade: &str, post_season: bool, robotevents: &RobotEvents) -> Result<Arc<Vec<SkillsRanking>>, reqwest::Error> {
+		let key = SkillsCacheKey(season_id, grade.to_string(), post_season);
+
+		if let Some(entry) = self.entries.write().await.get_mut(&key) {
+			entry.last_accessed = Instant::now();
+
+			if entry.fetched_at.elapsed() >= CACHE_TTL {
+				self.spawn_refresh(key.clone(), robotevents);
+			}
+
+			return Ok(entry.rankings.clone());
+		}
+
+		// Nothing is cached yet, so wait for our turn to fetch. If another request fetched the same
+		// leaderboard while we were waiting, its result is used instead of downloading it again.
+		let lock = self.fetch_lock(&key).await;
+		let _guard = lock.lock().await;
+
+		if let Some(entry) = self.entries.read().await.get(&key) {
+			return Ok(entry.rankings.clone());
+		}
+
+		self.fetch(&key, robotevents).await
+	}
+
+	pub async fn get_team_ranking(&self, team: &robotevents::schema::Team, season_id: i32, post_season: bool, robotevents: &RobotEvents) -> Result<Option<TeamSkillsRanking>, reqwest::Error> {
+		let rankings = self.get_rankings(season_id, &team.grade.to_string(), post_season, robotevents).await?;
+
+		let Some(ranking) = rankings.iter().find(|ranking| ranking.team.id == team.id).cloned() else {
+			return Ok(None);
 		};
 
-		Ok(match rankings.iter().find(|ranking| ranking.team.id == team.id) {
-			Some(r) => Some(r.clone()),
-			None => None,
-		})
+		// Regional and country ranks are the team's position on the world leaderboard after filtering out
+		// teams from elsewhere.
+		let rank_among = |same_area: &dyn Fn(&TeamV1) -> bool| {
+			rankings
+				.iter()
+				.filter(|other| same_area(&other.team))
+				.position(|other| other.team.id == team.id)
+				.map(|idx| idx + 1)
+				.unwrap_or_default()
+		};
+
+		Ok(Some(TeamSkillsRanking {
+			region_rank: rank_among(&|other| other.event_region_id == ranking.team.event_region_id),
+			country_rank: rank_among(&|other| other.country == ranking.team.country),
+			ranking,
+		}))
+	}
+
+	/// Spawns a task that periodically refreshes hot leaderboards ahead of time, so that users rarely have
+	/// to wait on a download or see a stale leaderboard.
+	///
+	/// Every `interval`, any leaderboard that was requested within the last day and will go stale before
+	/// the next check is refreshed.
+	pub fn spawn_refresh_task(&self, robotevents: RobotEvents, interval: Duration) {
+		let cache = self.clone();
+
+		tokio::spawn(async move {
+			loop {
+				tokio::time::sleep(interval).await;
+
+				let due_keys = cache.entries
+					.read()
+					.await
+					.iter()
+					.filter(|(_, entry)| entry.last_accessed.elapsed() < HOT_WINDOW)
+					.filter(|(_, entry)| entry.fetched_at.elapsed() + interval >= CACHE_TTL)
+					.map(|(key, _)| key.clone())
+					.collect::<Vec<_>>();
+
+				for key in due_keys {
+					let lock = cache.fetch_lock(&key).await;
+					let _guard = lock.lock().await;
+
+					if let Err(err) = cache.fetch(&key, &robotevents).await {
+						println!("Failed to refresh skills leaderboard for {key:?}: {err}");
+					}
+				}
+			}
+		});
 	}
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
+use crate::api::skills::SkillsRanking;
+use crate::storage::{self, Storage, StorageError};
+
+/// How often a team's entry is recorded when only its rank has changed (in seconds).
+///
+/// One team's new score shifts the rank of every team below it, so recording every rank change would
+/// save most of the leaderboard on every refresh.
+const RANK_ONLY_INTERVAL: i64 = 60 * 60 * 24;
+
+/// How long snapshots are kept for (in seconds), which covers the current and previous season.
+const RETENTION: i64 = 60 * 60 * 24 * 365 * 2;
+
+/// A single team's position on a skills leaderboard at the time a snapshot was taken.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct SkillsSnapshotEntry {
+	pub team_id: i32,
+	pub rank: i32,
+	pub max_driver: i32,
+	pub max_programming: i32,
+	pub score: i32,
+}
+
+/// The entries of a skills leaderboard that changed since the last time it was recorded, taken whenever
+/// [`crate::api::skills::SkillsCache`] downloads it.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct SkillsSnapshot {
+	/// Unix timestamp (in seconds) of when the leaderboard was fetched.
+	pub taken_at: i64,
+	pub entries: Vec<SkillsSnapshotEntry>,
+}
+
+/// A team's leaderboard entry from a single snapshot, along with when that snapshot was taken.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct SkillsHistoryPoint {
+	pub taken_at: i64,
+	pub entry: SkillsSnapshotEntry,
+}
+
+/// History of skills leaderboard snapshots, kept in the bot's [`Storage`].
+#[derive(Debug, Clone)]
+pub struct SkillsHistory {
+	storage: Storage,
+}
+
+impl SkillsHistory {
+	/// Creates a history that saves its snapshots to `storage`.
+	pub fn new(storage: Storage) -> Self {
+		Self { storage }
+	}
+
+	/// Saves a snapshot of a freshly fetched leaderboard.
+	///
+	/// Only teams whose scores changed since their last recorded entry are saved, along with teams whose
+	/// rank changed if they haven't been recorded in the last day. Snapshots older than two years are
+	/// deleted at the same time.
+	pub async fn record(&self, season_id: i32, grade: &str, post_season: bool, rankings: &[SkillsRanking]) -> Result<(), StorageError> {
+		let taken_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs() as i64).unwrap_or_default();
+		let entries = rankings
+			.iter()
+			.map(|ranking| SkillsSnapshotEntry {
+				team_id: ranking.team.id,
+				rank: ranking.rank,
+				max_driver: ranking.scores.max_driver,
+				max_programming: ranking.scores.max_programming,
+				score: ranking.scores.score,
+			})
+			.collect::<Vec<_>>();
+		let grade = grade.to_string();
+
+		storage::run(&self.storage, move |storage| {
+			let latest = storage
+				.latest_skills_entries(season_id, &grade, post_season)?
+				.into_iter()
+				.map(|point| (point.entry.team_id, point))
+				.collect::<HashMap<_, _>>();
+
+			let snapshot = SkillsSnapshot {
+				taken_at,
+				entries: entries
+					.into_iter()
+					.filter(|entry| match latest.get(&entry.team_id) {
+						Some(point) => {
+							let scores_changed = (entry.max_driver, entry.max_programming, entry.score)
+								!= (point.entry.max_driver, point.entry.max_programming, point.entry.score);
+							let rank_changed = entry.rank != point.entry.rank;
+
+							scores_changed || (rank_changed && taken_at - point.taken_at >= RANK_ONLY_INTERVAL)
+						},
+						None => true,
+					})
+					.collect(),
+			};
+
+			if !snapshot.entries.is_empty() {
+				storage.save_skills_snapshot(season_id, &grade, post_season, &snapshot)?;
+			}
+			storage.prune_skills_snapshots(taken_at - RETENTION)
+		}).await
+	}
+
+	/// Returns every recorded leaderboard entry for a team, oldest first.
+	///
+	/// Entries are only recorded when they change, so each point is where the team stood from then until
+	/// the next one.
+	pub async fn team_history(&self, team_id: i32, season_id: i32, grade: &str, post_season: bool) -> Result<Vec<SkillsHistoryPoint>, StorageError> {
+		let grade = grade.to_string();
+
+		storage::run(&self.storage, move |storage| storage.team_skills_history(team_id, season_id, &grade, post_season)).await
+	}
+}
\ No newline at end of file
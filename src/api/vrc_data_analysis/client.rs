@@ -21,20 +21,20 @@ impl VRCDataAnalysis {
         &self,
         endpoint: impl AsRef<str>,
     ) -> Result<reqwest::Response, reqwest::Error> {
-        Ok(self
+        self
             .req_client
             .get(format!("{API_BASE}{}", endpoint.as_ref()))
             .header("accept-language", "en")
             .header(USER_AGENT, "RoboStats Discord Bot")
             .timeout(Duration::from_secs(10))
             .send()
-            .await?)
+            .await
     }
 
     pub async fn team_info(&self, team_number: &str) -> Result<TeamInfo, reqwest::Error> {
         let response = self.request(format!("/team/{team_number}")).await?;
 
-        Ok(response.json().await?)
+        response.json().await
     }
 
     pub async fn predict_match(
@@ -49,10 +49,9 @@ impl VRCDataAnalysis {
             ))
             .await?;
 
-        Ok(response.json().await?)
+        response.json().await
     }
 
-    #[allow(unused)]
     pub async fn ccwm(
         &self,
         red_alliance: (&str, &str),
@@ -65,6 +64,6 @@ impl VRCDataAnalysis {
             ))
             .await?;
 
-        Ok(response.json().await?)
+        response.json().await
     }
 }
\ No newline at end of file
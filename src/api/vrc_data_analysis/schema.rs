@@ -33,6 +33,7 @@ pub struct Prediction {
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct CCWM {
     pub b1_awp_per_match: f64,
     pub b2_awp_per_match: f64,
@@ -0,0 +1,207 @@
+use serenity::all::{CommandDataOptionValue, CommandOptionType};
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+    EditInteractionResponse,
+};
+use serenity::client::Context;
+use serenity::futures::future::join_all;
+use serenity::model::{application::CommandInteraction, Color};
+
+use robotevents::{
+    RobotEvents,
+    query::{PaginatedQuery, TeamAwardsQuery},
+    schema::{IdInfo, PaginatedResponse, Season},
+};
+
+use crate::api::skills::{SkillsCache, TeamSkillsRanking};
+use crate::api::vrc_data_analysis::VRCDataAnalysis;
+use crate::commands::TeamCommand;
+use crate::guild_config::GuildConfig;
+
+/// Option names for each team slot. The first two are required.
+const TEAM_OPTIONS: [&str; 4] = ["team1", "team2", "team3", "team4"];
+
+/// Handler for the "/compare" command.
+/// Puts the skills, match statistics and awards of up to four teams side by side in a single embed.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct CompareCommand;
+
+impl CompareCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command(program_list: Option<PaginatedResponse<IdInfo>>) -> CreateCommand {
+        let mut program_opt = CreateCommandOption::new(CommandOptionType::Integer, "program", "Program Name").required(false);
+        if let Some(program_list) = program_list {
+            for program in program_list.data.iter() {
+                program_opt = program_opt.add_int_choice(&program.name, program.id);
+            }
+        }
+
+        let mut command = CreateCommand::new("compare").description("Compares up to four teams side by side");
+        for (idx, name) in TEAM_OPTIONS.iter().enumerate() {
+            command = command.add_option(
+                CreateCommandOption::new(CommandOptionType::String, *name, format!("Team Number #{}", idx + 1))
+                    .required(idx < 2)
+            );
+        }
+
+        command
+            .add_option(program_opt)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "season", "Season years (e.g. 2023-2024, defaults to the current season)")
+                    .required(false)
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Boolean, "post_season", "Compare post-season (e.g. Worlds) skills rankings instead of the regular season")
+                    .required(false)
+            )
+    }
+
+    /// Builds the embed field comparing a single team.
+    ///
+    /// Returns the field's name and contents. Data that fails to load is noted in the field rather than
+    /// failing the whole comparison.
+    #[allow(clippy::too_many_arguments)]
+    async fn team_field(
+        team_number: &str,
+        program_id_filter: Option<i32>,
+        season_years: Option<&str>,
+        post_season: bool,
+        robotevents: &RobotEvents,
+        vrc_data_analysis: &VRCDataAnalysis,
+        skills_cache: &SkillsCache,
+        season_list: &PaginatedResponse<Season>,
+    ) -> (String, String) {
+        let Ok(team) = TeamCommand::new(team_number, program_id_filter).find_robotevents_team(robotevents).await else {
+            return (team_number.to_string(), "Failed to find a RobotEvents team with this number.".to_string());
+        };
+
+        // Seasons are matched by their years, since season names change every year.
+        let Some(season) = season_list.data
+            .iter()
+            .filter(|season| season.program.id == team.program.id)
+            .filter(|season| match season_years {
+                Some(years) => format!("{}-{}", season.years_start, season.years_end) == years,
+                None => true,
+            })
+            .max_by_key(|season| season.years_start)
+        else {
+            return (team.number, "Failed to find a season matching these options.".to_string());
+        };
+
+        // vrc-data-analysis only has data for the current season.
+        let is_current_season = season_list.data
+            .iter()
+            .filter(|s| s.program.id == team.program.id)
+            .all(|s| s.years_start <= season.years_start);
+
+        let awards_query = TeamAwardsQuery::new().season(season.id).per_page(250);
+        let (skills_ranking, data_analysis, awards) = tokio::join!(
+            skills_cache.get_team_ranking(&team, season.id, post_season, robotevents),
+            async {
+                if team.program.id == 1 && is_current_season {
+                    Some(vrc_data_analysis.team_info(&team.number).await)
+                } else {
+                    None
+                }
+            },
+            team.awards(robotevents, awards_query),
+        );
+
+        let mut value = format!("*{}*\n", team.team_name);
+
+        let skills_label = if post_season { "Post-Season Skills" } else { "Skills" };
+        value.push_str(&match skills_ranking {
+            Ok(Some(TeamSkillsRanking { ranking, .. })) => format!(
+                "{skills_label}: **#{}** ({})\nDriver: **{}**\nProgramming: **{}**\n",
+                ranking.rank, ranking.scores.score, ranking.scores.max_driver, ranking.scores.max_programming
+            ),
+            Ok(None) => format!("{skills_label}: *No runs*\n"),
+            Err(_) => format!("{skills_label}: *Unavailable*\n"),
+        });
+
+        value.push_str(&match data_analysis {
+            Some(Ok(analysis)) => format!(
+                "TrueSkill: **{:.2}** (#{})\nOPR: **{:.2}**\nDPR: **{:.2}**\nCCWM: **{:.2}**\nRecord: **{}-{}-{}**\n",
+                analysis.trueskill,
+                analysis.trueskill_ranking,
+                analysis.opr,
+                analysis.dpr,
+                analysis.ccwm,
+                analysis.total_wins,
+                analysis.total_losses,
+                analysis.total_ties,
+            ),
+            Some(Err(_)) => "TrueSkill: *Unavailable*\n".to_string(),
+            None if team.program.id != 1 => "TrueSkill: *VRC only*\n".to_string(),
+            None => "TrueSkill: *Current season only*\n".to_string(),
+        });
+
+        value.push_str(&match awards {
+            Ok(awards) => format!("Awards: **{}**", awards.data.len()),
+            Err(_) => "Awards: *Unavailable*".to_string(),
+        });
+
+        (format!("{} ({})", team.number, season.name), value)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        vrc_data_analysis: &VRCDataAnalysis,
+        skills_cache: &SkillsCache,
+        season_list: Option<&PaginatedResponse<Season>>,
+        config: &GuildConfig,
+    ) -> EditInteractionResponse {
+        let option = |name: &str| interaction.data.options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+        let string_option = |name: &str| match option(name) {
+            Some(CommandDataOptionValue::String(value)) => Some(value.trim().to_string()),
+            _ => None,
+        };
+
+        let mut team_numbers: Vec<String> = Vec::new();
+        for number in TEAM_OPTIONS.iter().filter_map(|name| string_option(name)) {
+            if !team_numbers.iter().any(|other| other.eq_ignore_ascii_case(&number)) {
+                team_numbers.push(number);
+            }
+        }
+        if team_numbers.len() < 2 {
+            return EditInteractionResponse::new().content("At least two different teams are needed for a comparison.");
+        }
+
+        let program_id_filter = match option("program") {
+            Some(CommandDataOptionValue::Integer(id)) => i32::try_from(*id).ok(),
+            _ => config.default_program,
+        };
+        let season_years = string_option("season").or(config.default_season.clone());
+        let post_season = matches!(option("post_season"), Some(CommandDataOptionValue::Boolean(true)));
+
+        let Some(season_list) = season_list else {
+            return EditInteractionResponse::new().content("Failed to fetch season list from RobotEvents.");
+        };
+
+        let fields = join_all(team_numbers.iter().map(|number| {
+            Self::team_field(
+                number,
+                program_id_filter,
+                season_years.as_deref(),
+                post_season,
+                robotevents,
+                vrc_data_analysis,
+                skills_cache,
+                season_list,
+            )
+        }))
+        .await;
+
+        let embed = CreateEmbed::new()
+            .title(format!("Comparing {}", team_numbers.join(" vs. ")))
+            .fields(fields.into_iter().map(|(name, value)| (name, value, true)))
+            .footer(CreateEmbedFooter::new("Skills from RobotEvents. TrueSkill & Match Data provided by vrc-data-analysis.com"))
+            .color(Color::from_rgb(210, 38, 48));
+
+        EditInteractionResponse::new().add_embed(embed)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,171 @@
+use serenity::all::{ChannelType, CommandDataOptionValue, CommandOptionType, Permissions};
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponseMessage,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use robotevents::schema::{IdInfo, PaginatedResponse, Season};
+
+use crate::guild_config::GuildConfig;
+use crate::storage::Storage;
+
+/// Handler for the "/config" command.
+/// Lets guild admins change the defaults that every command uses in their server.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ConfigCommand;
+
+impl ConfigCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command(program_list: Option<PaginatedResponse<IdInfo>>) -> CreateCommand {
+        let mut program_opt = CreateCommandOption::new(CommandOptionType::Integer, "program", "Program Name (leave empty to clear)").required(false);
+        if let Some(program_list) = program_list {
+            for program in program_list.data.iter() {
+                program_opt = program_opt.add_int_choice(&program.name, program.id);
+            }
+        }
+
+        let channel_opt = CreateCommandOption::new(CommandOptionType::Channel, "channel", "Channel")
+            .channel_types(vec![ChannelType::Text, ChannelType::News])
+            .required(true);
+
+        CreateCommand::new("config")
+            .description("Changes RoboStats settings for this server")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .set_options(vec![
+                CreateCommandOption::new(CommandOptionType::SubCommand, "show", "Shows this server's settings"),
+                CreateCommandOption::new(CommandOptionType::SubCommand, "program", "Sets the program that team lookups default to")
+                    .add_sub_option(program_opt),
+                CreateCommandOption::new(CommandOptionType::SubCommand, "season", "Sets the season that commands default to")
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::String, "season", "Season years (e.g. 2023-2024, leave empty to use the current season)")
+                            .required(false)
+                    ),
+                CreateCommandOption::new(CommandOptionType::SubCommand, "ephemeral", "Sets whether responses are only visible to the user who ran the command")
+                    .add_sub_option(
+                        CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Only show responses to the user who ran the command")
+                            .required(true)
+                    ),
+                CreateCommandOption::new(CommandOptionType::SubCommand, "allow_channel", "Allows commands in a channel. Commands work everywhere until a channel is allowed.")
+                    .add_sub_option(channel_opt.clone()),
+                CreateCommandOption::new(CommandOptionType::SubCommand, "disallow_channel", "Removes a channel from the allowed channels")
+                    .add_sub_option(channel_opt),
+                CreateCommandOption::new(CommandOptionType::SubCommand, "reset", "Resets every setting to its default"),
+            ])
+    }
+
+    /// Constructs an embed summarizing a guild's settings.
+    fn embed(config: &GuildConfig, program_list: Option<&PaginatedResponse<IdInfo>>) -> CreateEmbed {
+        let program_name = config.default_program.map(|id| {
+            program_list
+                .and_then(|programs| programs.data.iter().find(|program| program.id == id))
+                .map(|program| program.name.clone())
+                .unwrap_or(format!("Program #{id}"))
+        });
+
+        CreateEmbed::new()
+            .title("RoboStats Settings")
+            .field("Default Program", program_name.unwrap_or("None".to_string()), true)
+            .field("Default Season", config.default_season.clone().unwrap_or("Current Season".to_string()), true)
+            .field("Ephemeral Responses", if config.ephemeral { "Enabled" } else { "Disabled" }, true)
+            .field(
+                "Allowed Channels",
+                if config.allowed_channels.is_empty() {
+                    "All Channels".to_string()
+                } else {
+                    config.allowed_channels.iter().map(|id| format!("<#{id}>")).collect::<Vec<_>>().join(", ")
+                },
+                false,
+            )
+            .color(Color::from_rgb(210, 38, 48))
+    }
+
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        storage: Option<&Storage>,
+        program_list: Option<&PaginatedResponse<IdInfo>>,
+        season_list: Option<&PaginatedResponse<Season>>,
+    ) -> CreateInteractionResponseMessage {
+        let Some(guild_id) = interaction.guild_id else {
+            return CreateInteractionResponseMessage::new().content("Settings can only be changed from within a server.");
+        };
+        let Some(storage) = storage else {
+            return CreateInteractionResponseMessage::new().content("Settings aren't available on this instance of the bot.");
+        };
+
+        let Some(subcommand) = interaction.data.options.first() else {
+            return CreateInteractionResponseMessage::new().content("Invalid subcommand option.");
+        };
+        let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+            return CreateInteractionResponseMessage::new().content("Invalid subcommand option.");
+        };
+        let option = |name: &str| options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+
+        let mut config = GuildConfig::load(Some(storage), Some(guild_id.get())).await;
+
+        match subcommand.name.as_str() {
+            "show" => {},
+            "program" => {
+                config.default_program = match option("program") {
+                    Some(CommandDataOptionValue::Integer(id)) => i32::try_from(*id).ok(),
+                    _ => None,
+                };
+            },
+            "season" => {
+                config.default_season = match option("season") {
+                    Some(CommandDataOptionValue::String(years)) => {
+                        let years = years.trim().to_string();
+
+                        // Seasons are matched by their years, since season names change every year.
+                        let exists = season_list.is_some_and(|seasons| {
+                            seasons.data
+                                .iter()
+                                .any(|season| format!("{}-{}", season.years_start, season.years_end) == years)
+                        });
+                        if !exists {
+                            return CreateInteractionResponseMessage::new()
+                                .content(format!("Couldn't find a season for {years}. Try something like 2023-2024."));
+                        }
+
+                        Some(years)
+                    },
+                    _ => None,
+                };
+            },
+            "ephemeral" => {
+                config.ephemeral = matches!(option("enabled"), Some(CommandDataOptionValue::Boolean(true)));
+            },
+            "allow_channel" => {
+                if let Some(CommandDataOptionValue::Channel(channel_id)) = option("channel") {
+                    if !config.allowed_channels.contains(&channel_id.get()) {
+                        config.allowed_channels.push(channel_id.get());
+                    }
+                }
+            },
+            "disallow_channel" => {
+                if let Some(CommandDataOptionValue::Channel(channel_id)) = option("channel") {
+                    config.allowed_channels.retain(|id| *id != channel_id.get());
+                }
+            },
+            "reset" => config = GuildConfig::default(),
+            _ => return CreateInteractionResponseMessage::new().content("Invalid subcommand option."),
+        }
+
+        if subcommand.name != "show" {
+            if let Err(err) = config.save(storage, guild_id.get()).await {
+                return CreateInteractionResponseMessage::new().add_embed(
+                    CreateEmbed::new()
+                        .title("Failed to save settings.")
+                        .description(format!("```rs\n{err:?}```")),
+                );
+            }
+        }
+
+        CreateInteractionResponseMessage::new()
+            .add_embed(Self::embed(&config, program_list))
+            .ephemeral(true)
+    }
+}
\ No newline at end of file
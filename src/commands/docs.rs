@@ -0,0 +1,81 @@
+use serenity::all::{CommandDataOptionValue, CommandOptionType};
+use serenity::builder::{
+    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponseMessage,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use crate::docs::DocsIndex;
+
+/// Discord won't show more than 25 autocomplete choices.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+/// Handler for the "/docs" command.
+/// Looks up a class, struct or method from LemLib's API reference.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct DocsCommand;
+
+impl DocsCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command() -> CreateCommand {
+        CreateCommand::new("docs")
+            .description("Looks up a class or method in the LemLib documentation")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "symbol", "Name of the class or method (e.g. moveToPoint)")
+                    .required(true)
+                    .set_autocomplete(true)
+            )
+    }
+
+    /// Suggests symbols matching the `symbol` option as the user types it.
+    pub fn autocomplete(&self, interaction: &CommandInteraction, index: &DocsIndex) -> CreateAutocompleteResponse {
+        let query = interaction.data.autocomplete().map(|opt| opt.value).unwrap_or_default();
+
+        index.search(query, MAX_AUTOCOMPLETE_CHOICES)
+            .into_iter()
+            .filter(|symbol| symbol.name.chars().count() <= 100)
+            .fold(CreateAutocompleteResponse::new(), |response, symbol| {
+                response.add_string_choice(&symbol.name, &symbol.name)
+            })
+    }
+
+    pub fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        index: &DocsIndex,
+    ) -> CreateInteractionResponseMessage {
+        let name = match interaction.data.options.iter().find(|opt| opt.name == "symbol").map(|opt| &opt.value) {
+            Some(CommandDataOptionValue::String(name)) => name.trim(),
+            _ => return CreateInteractionResponseMessage::new().content("Invalid symbol name."),
+        };
+
+        // Fall back to the closest match, since symbol names are easy to get slightly wrong.
+        let Some(symbol) = index.symbol(name).or_else(|| index.search(name, 1).into_iter().next()) else {
+            return CreateInteractionResponseMessage::new()
+                .content(format!("Couldn't find `{name}` in the {} documentation.", index.project));
+        };
+
+        let mut embed = CreateEmbed::new()
+            .title(&symbol.name)
+            .url(index.url(symbol))
+            .description(format!("```cpp\n{}\n```\n{}", symbol.signature, symbol.description))
+            .footer(CreateEmbedFooter::new(format!("{} {} | {}", index.project, index.version, symbol.kind)))
+            .color(Color::from_rgb(210, 38, 48));
+
+        if !symbol.parameters.is_empty() {
+            embed = embed.field(
+                "Parameters",
+                symbol.parameters
+                    .iter()
+                    .map(|parameter| format!("`{}`: {}", parameter.name, parameter.description))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                false,
+            );
+        }
+
+        CreateInteractionResponseMessage::new().add_embed(embed)
+    }
+}
\ No newline at end of file
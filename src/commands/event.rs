@@ -0,0 +1,743 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use robotevents::query::PaginatedQuery;
+use serenity::all::{
+    ButtonStyle, CommandDataOptionValue, CommandOptionType, ComponentInteraction,
+    ComponentInteractionDataKind, ReactionType,
+};
+use serenity::builder::{
+    CreateActionRow, CreateAutocompleteResponse, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind,
+    CreateSelectMenuOption, EditInteractionResponse, CreateEmbedFooter,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use robotevents::{
+    RobotEvents,
+    query::{EventTeamsQuery, EventAwardsQuery, DivisionRankingsQuery},
+    schema::{PaginatedResponse, Team, Event, Award, IdInfo, Ranking, TeamAwardWinner}
+};
+
+use crate::guild_config::GuildConfig;
+use crate::lookup_index::{IndexedEvent, LookupIndex};
+
+const MAX_PER_PAGE: i32 = 250;
+
+/// Number of rankings shown per embed on the rankings page.
+///
+/// Each ranking is an embed field, and Discord doesn't allow more than 25 fields in a single embed.
+const RANKINGS_PER_PAGE: usize = 15;
+
+/// Discord caps embed descriptions at 4096 characters, so long lists are cut off a bit before that.
+const MAX_DESCRIPTION_LENGTH: usize = 4000;
+
+/// Discord won't show more than 25 autocomplete choices.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+/// Program codes used in event SKUs, by RobotEvents program ID.
+///
+/// Programs have been renamed over the years (e.g. VRC events became V5RC events in 2024), so both the older
+/// and newer codes are listed.
+const SKU_PROGRAM_CODES: [(i32, &[&str]); 4] = [
+    (1, &["VRC", "V5RC"]),
+    (4, &["VEXU", "VURC"]),
+    (41, &["VIQC", "VIQRC"]),
+    (57, &["VAIC", "VAIRC"]),
+];
+
+/// Returns a link to an event's page on RobotEvents.
+///
+/// RobotEvents uses a different URL slug for each program, which isn't included in API responses.
+pub fn robotevents_event_url(program: &IdInfo, sku: &str) -> String {
+    format!(
+        "https://robotevents.com/robot-competitions/{}/{}.html",
+        match program.id {
+            1 => "vex-robotics-competition",
+            4 => "college-competition",
+            41 => "vex-iq-competition",
+            57 => "vex-ai-competition",
+            46 => "tsavrc",
+            47 => "tsaviqc",
+            56 => "vex-factory-automation-competition",
+            _ => program.code.as_deref().unwrap_or("UNKNOWN"),
+        },
+        sku
+    )
+}
+
+/// Returns whether an event SKU (e.g. "RE-VRC-23-1234") is from a guild's default program and season, for
+/// whichever of the two have been set.
+fn matches_guild_defaults(sku: &str, config: &GuildConfig) -> bool {
+    let mut parts = sku.split('-').skip(1);
+    let (Some(program_code), Some(season_year)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+
+    let program_matches = match config.default_program {
+        Some(program_id) => SKU_PROGRAM_CODES
+            .iter()
+            .any(|(id, codes)| *id == program_id && codes.iter().any(|code| code.eq_ignore_ascii_case(program_code))),
+        None => true,
+    };
+
+    // SKUs include the last two digits of the year that the season started in.
+    let season_matches = match &config.default_season {
+        Some(years) => years.get(2..4) == Some(season_year),
+        None => true,
+    };
+
+    program_matches && season_matches
+}
+
+/// Fetches an event by its SKU (e.g. "RE-VRC-23-1234").
+///
+/// [`robotevents::query::EventsQuery::sku`] only accepts numbers, so the query string is built by hand.
+/// Characters that can't appear in a SKU are dropped so that they can't change the query.
+pub async fn find_event_by_sku(robotevents: &RobotEvents, sku: &str) -> Result<Option<Event>, reqwest::Error> {
+    let sku = sku
+        .trim()
+        .to_uppercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect::<String>();
+
+    let events: PaginatedResponse<Event> = robotevents
+        .request(format!("/events?sku%5B%5D={sku}"))
+        .await?
+        .json()
+        .await?;
+
+    Ok(events.data.into_iter().next())
+}
+
+/// Returns the team number of an award winner.
+///
+/// robotevents doesn't expose the team of a [`TeamAwardWinner`], so it's read back out of the winner's
+/// serialized form instead.
+fn award_winner_team(winner: &TeamAwardWinner) -> Option<String> {
+    serde_json::to_value(winner).ok()?
+        .get("team")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Represents a possible embed sent by the `/event` command.
+///
+/// - The Overview embed displays general information about an event.
+/// - The Teams embed displays the list of teams registered for an event.
+/// - The Divisions embed displays the divisions that an event is split into.
+/// - The Awards embed displays the awards offered at an event and their winners.
+/// - The Rankings embed displays the qualification rankings of a single division.
+///
+/// > Different embed "pages" may require different, separately-fetched pieces of data.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum EventEmbedPage {
+    #[default]
+    Overview,
+    Teams,
+    Divisions,
+    Awards,
+    Rankings,
+}
+
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ParseEventEmbedPageError;
+
+impl FromStr for EventEmbedPage {
+    type Err = ParseEventEmbedPageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "overview" => Ok(Self::Overview),
+            "teams" => Ok(Self::Teams),
+            "divisions" => Ok(Self::Divisions),
+            "awards" => Ok(Self::Awards),
+            "rankings" => Ok(Self::Rankings),
+            _ => Err(ParseEventEmbedPageError),
+        }
+    }
+}
+
+impl std::fmt::Display for EventEmbedPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Overview => "overview",
+            Self::Teams => "teams",
+            Self::Divisions => "divisions",
+            Self::Awards => "awards",
+            Self::Rankings => "rankings",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EventCommandRequestError;
+
+/// Handler for the "/event" command.
+/// The event command serves the purpose of displaying information about a singular RobotEvents event,
+/// looked up by its SKU.
+///
+/// Like [`super::TeamCommand`], data stored in this struct is lazily fetched over HTTP and cached for
+/// the lifetime of the interaction.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct EventCommand {
+    /// Current user-selected [`EventEmbedPage`].
+    current_page: EventEmbedPage,
+
+    /// Event SKU requested by the user on the initial command interaction.
+    sku: Option<String>,
+
+    /// Event fetched from robotevents.
+    /// > This will be `None` if the request fails or hasn't been made yet.
+    event: Option<Event>,
+
+    /// List of teams registered for the event.
+    teams: Option<PaginatedResponse<Team>>,
+
+    /// List of awards offered at the event.
+    awards: Option<PaginatedResponse<Award>>,
+
+    /// Current user-selected division ID, used by the rankings page.
+    current_division: Option<i32>,
+
+    /// Current page of rankings being viewed, starting at zero.
+    rankings_page: usize,
+
+    /// Qualification rankings for each division that has been viewed, keyed by division ID.
+    rankings: HashMap<i32, Vec<Ranking>>,
+}
+
+impl EventCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command() -> CreateCommand {
+        CreateCommand::new("event")
+            .description("Displays information about an event")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "sku", "Event SKU (e.g. RE-VRC-23-1234)")
+                    .required(true)
+                    .set_autocomplete(true)
+            )
+    }
+
+    /// Suggests events from the [`LookupIndex`] for whichever SKU option is being typed in, matching either
+    /// the SKU or the event's name. This is shared with other commands that take SKUs, like `/predict`.
+    ///
+    /// Events from the guild's default program and season are suggested first.
+    pub async fn sku_suggestions(interaction: &CommandInteraction, lookup_index: &LookupIndex, config: &GuildConfig) -> CreateAutocompleteResponse {
+        let query = interaction.data.autocomplete().map(|opt| opt.value).unwrap_or_default();
+
+        let mut events = lookup_index.search_events(query, usize::MAX).await;
+        // The sort is stable, so the index's own ordering is kept within each group.
+        events.sort_by_key(|event| !matches_guild_defaults(&event.sku, config));
+
+        events
+            .into_iter()
+            .take(MAX_AUTOCOMPLETE_CHOICES)
+            .fold(CreateAutocompleteResponse::new(), |response, event| {
+                // Choice names are limited to 100 characters, so long event names are cut short before the SKU.
+                let name = event.name.chars().take(97usize.saturating_sub(event.sku.len())).collect::<String>();
+                response.add_string_choice(format!("{name} ({})", event.sku), event.sku)
+            })
+    }
+
+    /// Generate the message components associated with this command, including the page select menu,
+    /// along with the division select menu and page buttons on the rankings page.
+    ///
+    /// See [`super::TeamCommand::components`] for why this is rebuilt on every edit.
+    pub fn components(&self, page_selection: EventEmbedPage) -> Vec<CreateActionRow> {
+        let mut components = vec![CreateActionRow::SelectMenu(CreateSelectMenu::new(
+            "event_page_select",
+            CreateSelectMenuKind::String {
+                options: vec![
+                    CreateSelectMenuOption::new("Event Overview", "option_event_overview")
+                        .emoji(ReactionType::Unicode("🗓️".to_string()))
+                        .description("General information about the event")
+                        .default_selection(page_selection == EventEmbedPage::Overview),
+                    CreateSelectMenuOption::new("Teams", "option_event_teams")
+                        .emoji(ReactionType::Unicode("🤖".to_string()))
+                        .description("Teams registered for this event")
+                        .default_selection(page_selection == EventEmbedPage::Teams),
+                    CreateSelectMenuOption::new("Divisions", "option_event_divisions")
+                        .emoji(ReactionType::Unicode("🗂️".to_string()))
+                        .description("Divisions at this event")
+                        .default_selection(page_selection == EventEmbedPage::Divisions),
+                    CreateSelectMenuOption::new("Awards", "option_event_awards")
+                        .emoji(ReactionType::Unicode("🏆".to_string()))
+                        .description("Awards offered at this event")
+                        .default_selection(page_selection == EventEmbedPage::Awards),
+                    CreateSelectMenuOption::new("Rankings", "option_event_rankings")
+                        .emoji(ReactionType::Unicode("📈".to_string()))
+                        .description("Qualification rankings for each division")
+                        .default_selection(page_selection == EventEmbedPage::Rankings),
+                ],
+            },
+        ))];
+
+        if page_selection != EventEmbedPage::Rankings {
+            return components;
+        }
+
+        // Events with a single division don't need a division selector.
+        if let Some(event) = &self.event {
+            if event.divisions.len() > 1 {
+                components.push(CreateActionRow::SelectMenu(CreateSelectMenu::new(
+                    "event_division_select",
+                    CreateSelectMenuKind::String {
+                        options: event.divisions
+                            .iter()
+                            .take(25)
+                            .map(|division| {
+                                CreateSelectMenuOption::new(
+                                    &division.name,
+                                    format!("option_division_{}", division.id),
+                                )
+                                .default_selection(Some(division.id) == self.current_division)
+                            })
+                            .collect(),
+                    },
+                )));
+            }
+        }
+
+        let page_count = self.rankings_page_count();
+        if page_count > 1 {
+            components.push(CreateActionRow::Buttons(vec![
+                CreateButton::new("event_rankings_previous")
+                    .label("Previous")
+                    .style(ButtonStyle::Secondary)
+                    .disabled(self.rankings_page == 0),
+                CreateButton::new("event_rankings_next")
+                    .label("Next")
+                    .style(ButtonStyle::Secondary)
+                    .disabled(self.rankings_page + 1 >= page_count),
+            ]));
+        }
+
+        components
+    }
+
+    /// Returns the number of rankings pages for the currently selected division, or zero if the rankings
+    /// haven't been fetched yet.
+    fn rankings_page_count(&self) -> usize {
+        self.current_division
+            .and_then(|division_id| self.rankings.get(&division_id))
+            .map(|rankings| rankings.len().div_ceil(RANKINGS_PER_PAGE))
+            .unwrap_or(0)
+    }
+
+    /// Constructs an embed based on a page variant and the event's data.
+    ///
+    /// Returned as an instance of [`serenity::builder::CreateEmbed`].
+    pub async fn embed(&mut self, page: EventEmbedPage, robotevents: &RobotEvents) -> CreateEmbed {
+        let event = match self.find_robotevents_event(robotevents).await {
+            Ok(event) => event,
+            Err(err) => {
+                return CreateEmbed::new()
+                    .title("Failed to fetch RobotEvents event data.")
+                    .description(format!("```rs\n{err:?}```"));
+            },
+        };
+
+        let mut embed = CreateEmbed::new()
+            .url(robotevents_event_url(&event.program, &event.sku))
+            .color(match event.program.id {
+                1 | 4 => Color::from_rgb(210, 38, 48), // VRC/VEXU
+                41 => Color::from_rgb(0, 119, 200), // VIQC
+                57 => Color::from_rgb(91, 91, 91), // VAIC
+                _ => Default::default(),
+            })
+            .footer(CreateEmbedFooter::new(&event.sku));
+
+        match page {
+            EventEmbedPage::Overview => {
+                let mut location = vec![event.location.city.clone()];
+                if let Some(region) = &event.location.region {
+                    location.push(region.clone());
+                }
+                location.push(event.location.country.clone());
+
+                embed = embed
+                    .title(&event.name)
+                    .description(&event.season.name)
+                    .field("Location", location.join(", "), false);
+
+                if let Some(venue) = &event.location.venue {
+                    if !venue.is_empty() {
+                        embed = embed.field("Venue", venue, false);
+                    }
+                }
+
+                // RobotEvents dates are ISO 8601 timestamps, but only the date portion is useful here.
+                let format_date = |date: &str| date.split('T').next().unwrap_or(date).to_string();
+                let (start, end) = (format_date(&event.start), format_date(&event.end));
+
+                embed = embed
+                    .field("Date", if start == end { start } else { format!("{start} to {end}") }, false)
+                    .field("Program", event.program.code.clone().unwrap_or(event.program.name.clone()), true)
+                    .field("Divisions", event.divisions.len().to_string(), true)
+                    .field("Ongoing", if event.ongoing { "Yes" } else { "No" }, true);
+            },
+            EventEmbedPage::Teams => {
+                let teams = if let Some(teams) = &self.teams {
+                    teams.clone()
+                } else {
+                    match event.teams(robotevents, EventTeamsQuery::new().per_page(MAX_PER_PAGE)).await {
+                        Ok(teams) => {
+                            self.teams = Some(teams.clone());
+                            teams
+                        },
+                        Err(err) => {
+                            return CreateEmbed::new()
+                                .title("Failed to fetch RobotEvents team data.")
+                                .description(format!("```rs\n{err:?}```"));
+                        },
+                    }
+                };
+
+                embed = embed.title(format!("{} Teams", event.name));
+
+                if teams.data.is_empty() {
+                    embed = embed.description("No teams are registered for this event.");
+                } else {
+                    let mut description = String::new();
+                    for (idx, team) in teams.data.iter().enumerate() {
+                        let line = format!("**{}** {}\n", team.number, team.team_name);
+
+                        if description.len() + line.len() > MAX_DESCRIPTION_LENGTH {
+                            description.push_str(&format!("*...and {} more.*", teams.data.len() - idx));
+                            break;
+                        }
+                        description.push_str(&line);
+                    }
+
+                    embed = embed
+                        .description(description)
+                        .footer(CreateEmbedFooter::new(format!("{} ({} Teams)", event.sku, teams.meta.total)));
+                }
+            },
+            EventEmbedPage::Divisions => {
+                embed = embed.title(format!("{} Divisions", event.name));
+
+                if event.divisions.is_empty() {
+                    embed = embed.description("This event has no divisions listed.");
+                }
+
+                for division in event.divisions.iter() {
+                    embed = embed.field(&division.name, format!("Division ID: {}", division.id), true);
+                }
+            },
+            EventEmbedPage::Awards => {
+                let awards = if let Some(awards) = &self.awards {
+                    awards.clone()
+                } else {
+                    match event.awards(robotevents, EventAwardsQuery::new().per_page(MAX_PER_PAGE)).await {
+                        Ok(awards) => {
+                            self.awards = Some(awards.clone());
+                            awards
+                        },
+                        Err(err) => {
+                            return CreateEmbed::new()
+                                .title("Failed to fetch RobotEvents awards data.")
+                                .description(format!("```rs\n{err:?}```"));
+                        },
+                    }
+                };
+
+                embed = embed.title(format!("{} Awards", event.name));
+
+                if awards.data.is_empty() {
+                    embed = embed.description("No awards found.");
+                }
+
+                // Awards with the same title (such as multiple Tournament Champions) are grouped into
+                // one field.
+                let mut categorized_awards: Vec<(String, Vec<String>)> = Vec::new();
+                let mut award_indices: HashMap<String, usize> = HashMap::new();
+                for award in awards.data {
+                    let winners = award.team_winners
+                        .iter()
+                        .filter_map(award_winner_team)
+                        .chain(award.individual_winners.iter().cloned())
+                        .collect::<Vec<_>>();
+
+                    let idx = *award_indices.entry(award.title.clone()).or_insert_with(|| {
+                        categorized_awards.push((award.title.clone(), Vec::new()));
+                        categorized_awards.len() - 1
+                    });
+                    categorized_awards[idx].1.extend(winners);
+                }
+
+                for (title, winners) in categorized_awards.iter().take(25) {
+                    embed = embed.field(
+                        title,
+                        if winners.is_empty() {
+                            "*Not yet awarded*".to_string()
+                        } else {
+                            winners.join(", ")
+                        },
+                        true
+                    );
+                }
+            },
+            EventEmbedPage::Rankings => {
+                let division = match self.current_division
+                    .and_then(|id| event.divisions.iter().find(|division| division.id == id))
+                    .or(event.divisions.first())
+                {
+                    Some(division) => division.clone(),
+                    None => {
+                        return embed
+                            .title(format!("{} Rankings", event.name))
+                            .description("This event has no divisions listed.");
+                    },
+                };
+                self.current_division = Some(division.id);
+
+                let rankings = if let Some(rankings) = self.rankings.get(&division.id) {
+                    rankings.clone()
+                } else {
+                    match event.division_rankings(division.id, robotevents, DivisionRankingsQuery::new().per_page(MAX_PER_PAGE)).await {
+                        Ok(rankings) => {
+                            let mut rankings = rankings.data;
+                            rankings.sort_by_key(|ranking| ranking.rank);
+
+                            self.rankings.insert(division.id, rankings.clone());
+                            rankings
+                        },
+                        Err(err) => {
+                            return CreateEmbed::new()
+                                .title("Failed to fetch RobotEvents rankings data.")
+                                .description(format!("```rs\n{err:?}```"));
+                        },
+                    }
+                };
+
+                embed = embed.title(if event.divisions.len() > 1 {
+                    format!("{} Rankings ({})", event.name, division.name)
+                } else {
+                    format!("{} Rankings", event.name)
+                });
+
+                if rankings.is_empty() {
+                    embed = embed.description("No rankings have been published for this division yet.");
+                } else {
+                    let page_count = self.rankings_page_count();
+                    self.rankings_page = self.rankings_page.min(page_count - 1);
+
+                    for ranking in rankings.iter().skip(self.rankings_page * RANKINGS_PER_PAGE).take(RANKINGS_PER_PAGE) {
+                        embed = embed.field(
+                            format!("#{} {}", ranking.rank, ranking.team.name),
+                            format!(
+                                "W-L-T: **{}-{}-{}**\nWP/AP/SP: **{}/{}/{}**\nAverage Points: **{:.1}**",
+                                ranking.wins,
+                                ranking.losses,
+                                ranking.ties,
+                                ranking.wp,
+                                ranking.ap,
+                                ranking.sp,
+                                ranking.average_points,
+                            ),
+                            true
+                        );
+                    }
+
+                    embed = embed.footer(CreateEmbedFooter::new(format!(
+                        "Page {} of {} ({} Teams)",
+                        self.rankings_page + 1,
+                        page_count,
+                        rankings.len()
+                    )));
+                }
+            },
+        }
+
+        embed
+    }
+
+    /// Generate an initial response message to a command interaction.
+    ///
+    /// The `/event` command takes a single argument, being the SKU of the event to look up on RobotEvents.
+    ///
+    /// > By default, this response will start on the event overview [`EventEmbedPage`].
+    pub async fn response(
+        &mut self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        lookup_index: &LookupIndex,
+    ) -> CreateInteractionResponseMessage {
+        self.sku = if let CommandDataOptionValue::String(sku) = &interaction.data.options[0].value {
+            Some(sku.trim().to_uppercase())
+        } else {
+            return CreateInteractionResponseMessage::new().content("Invalid event SKU.");
+        };
+
+        if let Ok(event) = self.find_robotevents_event(robotevents).await {
+            // Remember the event so that its SKU can be autocompleted next time.
+            lookup_index.add_event(IndexedEvent { sku: event.sku, name: event.name }).await;
+
+            CreateInteractionResponseMessage::new()
+                .embed(self.embed(self.current_page, robotevents).await)
+                .components(self.components(self.current_page))
+        } else {
+            CreateInteractionResponseMessage::new().content("Failed to find a RobotEvents event with this SKU.")
+        }
+    }
+
+    /// Returns the RobotEvents event data associated with this instance of [`Self`].
+    ///
+    /// If `self.event` happens to be `None`, this function will attempt to fetch the required information
+    /// from the RobotEvents API, but otherwise return the cached result.
+    pub async fn find_robotevents_event(
+        &mut self,
+        robotevents: &RobotEvents,
+    ) -> Result<Event, EventCommandRequestError> {
+        let sku = if let Some(sku) = &self.sku {
+            sku
+        } else {
+            return Err(EventCommandRequestError);
+        };
+
+        if let Some(event) = self.event.clone() {
+            return Ok(event);
+        }
+
+        if let Ok(Some(event)) = find_event_by_sku(robotevents, sku).await {
+            self.event = Some(event.clone()); // Cache value for later use.
+            return Ok(event);
+        }
+
+        Err(EventCommandRequestError)
+    }
+
+    /// Event handler that outputs a response message based on a user interaction with a message component
+    /// (such as a select menu or button).
+    pub async fn component_interaction_response(
+        &mut self,
+        ctx: &Context,
+        command_interaction: &CommandInteraction,
+        component_interaction: &ComponentInteraction,
+        robotevents: &RobotEvents,
+    ) -> CreateInteractionResponse {
+        if let ComponentInteractionDataKind::Button = &component_interaction.data.kind {
+            match component_interaction.data.custom_id.as_str() {
+                "event_rankings_previous" => self.rankings_page = self.rankings_page.saturating_sub(1),
+                "event_rankings_next" => self.rankings_page += 1,
+                _ => {
+                    return CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content("Unhandled component interaction. This shouldn't happen."),
+                    )
+                },
+            }
+
+            let message_edit = command_interaction
+                .edit_response(
+                    &ctx,
+                    EditInteractionResponse::new()
+                        .embed(self.embed(self.current_page, robotevents).await)
+                        .components(self.components(self.current_page)),
+                )
+                .await;
+
+            return if message_edit.is_ok() {
+                CreateInteractionResponse::Acknowledge
+            } else {
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new().content("Failed to edit embed."),
+                )
+            };
+        }
+
+        if let ComponentInteractionDataKind::StringSelect { values } = &component_interaction.data.kind {
+            let changed_value: &str = values.first().unwrap().as_ref();
+
+            let message_edit = if changed_value.starts_with("option_event_") { // User changed page
+                self.current_page = if let Ok(page) = changed_value.trim_start_matches("option_event_").parse::<EventEmbedPage>() {
+                    page
+                } else {
+                    return CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(format!("Failed to parse page for {}.", changed_value)),
+                    )
+                };
+
+                command_interaction
+                    .edit_response(
+                        &ctx,
+                        EditInteractionResponse::new()
+                            .embed(self.embed(self.current_page, robotevents).await)
+                            .components(self.components(self.current_page)),
+                    )
+                    .await
+            } else if changed_value.starts_with("option_division_") { // User changed division
+                let division_id = if let Ok(parsed_id) = changed_value.trim_start_matches("option_division_").parse::<i32>() {
+                    parsed_id
+                } else {
+                    return CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(format!("Failed to parse division ID for {}.", changed_value)),
+                    )
+                };
+
+                self.current_division = Some(division_id);
+                self.rankings_page = 0;
+
+                command_interaction
+                    .edit_response(
+                        &ctx,
+                        EditInteractionResponse::new()
+                            .embed(self.embed(self.current_page, robotevents).await)
+                            .components(self.components(self.current_page)),
+                    )
+                    .await
+            } else {
+                return CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("Unhandled component interaction. This shouldn't happen."),
+                )
+            };
+
+            if message_edit.is_ok() {
+                CreateInteractionResponse::Acknowledge
+            } else {
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new().content("Failed to edit embed."),
+                )
+            }
+        } else {
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content("Unhandled component interaction. This shouldn't happen."),
+            )
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_guild_defaults_by_program_and_season() {
+        let config = GuildConfig {
+            default_program: Some(41),
+            default_season: Some("2024-2025".to_string()),
+            ..Default::default()
+        };
+
+        assert!(matches_guild_defaults("RE-VIQRC-24-5830", &config));
+        assert!(!matches_guild_defaults("RE-VIQRC-23-1234", &config));
+        assert!(!matches_guild_defaults("RE-V5RC-24-5830", &config));
+        assert!(!matches_guild_defaults("not a sku", &config));
+    }
+
+    #[test]
+    fn matches_any_event_without_defaults() {
+        assert!(matches_guild_defaults("RE-VRC-23-1234", &GuildConfig::default()));
+    }
+}
@@ -0,0 +1,110 @@
+use serenity::all::{ChannelType, CommandDataOptionValue, CommandOptionType, Permissions};
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponseMessage,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use robotevents::{
+    RobotEvents,
+    schema::{IdInfo, PaginatedResponse},
+};
+
+use crate::commands::TeamCommand;
+use crate::guild_config::GuildConfig;
+use crate::watchlist::{Watchlist, MAX_TEAMS_PER_GUILD};
+
+/// Handler for the "/follow" command.
+/// Adds a team to the guild's watchlist, so that new awards, event registrations, match results and
+/// skills rank changes get posted to a channel. Without a team, lists the teams the guild follows.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct FollowCommand;
+
+impl FollowCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command(program_list: Option<PaginatedResponse<IdInfo>>) -> CreateCommand {
+        let mut program_opt = CreateCommandOption::new(CommandOptionType::Integer, "program", "Program Name").required(false);
+        if let Some(program_list) = program_list {
+            for program in program_list.data.iter() {
+                program_opt = program_opt.add_int_choice(&program.name, program.id);
+            }
+        }
+
+        CreateCommand::new("follow")
+            .description("Posts updates about a team to a channel, or lists the teams this server follows")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "number", "Team Number (leave empty to list followed teams)")
+                    .required(false)
+            )
+            .add_option(program_opt)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Channel, "channel", "Channel to post updates in (defaults to this channel)")
+                    .channel_types(vec![ChannelType::Text, ChannelType::News])
+                    .required(false)
+            )
+    }
+
+    /// Constructs an embed listing every team on a guild's watchlist.
+    async fn list_embed(watchlist: &Watchlist, guild_id: u64) -> CreateEmbed {
+        let teams = watchlist.teams(guild_id).await;
+
+        CreateEmbed::new()
+            .title("Followed Teams")
+            .description(if teams.is_empty() {
+                "This server isn't following any teams yet. Use `/follow <number>` to start.".to_string()
+            } else {
+                teams
+                    .iter()
+                    .map(|watched| format!("- **{}** in <#{}>", watched.team_number, watched.channel_id))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .footer(CreateEmbedFooter::new(format!("{}/{} Teams", teams.len(), MAX_TEAMS_PER_GUILD)))
+            .color(Color::from_rgb(210, 38, 48))
+    }
+
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        watchlist: &Watchlist,
+        config: &GuildConfig,
+    ) -> CreateInteractionResponseMessage {
+        let Some(guild_id) = interaction.guild_id else {
+            return CreateInteractionResponseMessage::new().content("Teams can only be followed from within a server.");
+        };
+
+        let option = |name: &str| interaction.data.options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+
+        let Some(CommandDataOptionValue::String(team_number)) = option("number") else {
+            return CreateInteractionResponseMessage::new().add_embed(Self::list_embed(watchlist, guild_id.get()).await);
+        };
+        let program_id_filter = match option("program") {
+            Some(CommandDataOptionValue::Integer(id)) => i32::try_from(*id).ok(),
+            _ => config.default_program,
+        };
+        let channel_id = match option("channel") {
+            Some(CommandDataOptionValue::Channel(channel_id)) => *channel_id,
+            _ => interaction.channel_id,
+        };
+
+        let Ok(team) = TeamCommand::new(team_number.trim(), program_id_filter).find_robotevents_team(robotevents).await else {
+            return CreateInteractionResponseMessage::new().content("Failed to find a RobotEvents team with this number.");
+        };
+
+        if !watchlist.follow(guild_id.get(), &team, channel_id.get()).await {
+            return CreateInteractionResponseMessage::new().content(format!(
+                "This server is already following {MAX_TEAMS_PER_GUILD} teams. Use `/unfollow` to make room first."
+            ));
+        }
+
+        CreateInteractionResponseMessage::new().content(format!(
+            "Now following **{}** ({}). Updates will be posted in <#{}>.",
+            team.number, team.team_name, channel_id
+        ))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,119 @@
+use serenity::all::{CommandDataOptionValue, CommandOptionType};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateInteractionResponseMessage};
+use serenity::client::Context;
+use serenity::model::application::CommandInteraction;
+
+use robotevents::{
+    RobotEvents,
+    schema::{IdInfo, PaginatedResponse},
+};
+
+use crate::commands::TeamCommand;
+use crate::guild_config::GuildConfig;
+use crate::storage::{self, Storage, UserLink};
+
+/// Handler for the "/iam" command.
+/// Links a Discord user to their team, so that commands like `/team` can default to it.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct IamCommand;
+
+impl IamCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command(program_list: Option<PaginatedResponse<IdInfo>>) -> CreateCommand {
+        let mut program_opt = CreateCommandOption::new(CommandOptionType::Integer, "program", "Program Name").required(false);
+        if let Some(program_list) = program_list {
+            for program in program_list.data.iter() {
+                program_opt = program_opt.add_int_choice(&program.name, program.id);
+            }
+        }
+
+        CreateCommand::new("iam")
+            .description("Links your Discord account to your team, or shows your linked team")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "number", "Team Number (leave empty to see your linked team)")
+                    .required(false)
+            )
+            .add_option(program_opt)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Boolean, "unlink", "Remove your linked team")
+                    .required(false)
+            )
+    }
+
+    /// Returns the team that a Discord user has linked with `/iam`, if any.
+    pub async fn linked_team(storage: Option<&Storage>, user_id: u64) -> Option<UserLink> {
+        let storage = storage?;
+
+        match storage::run(storage, move |storage| storage.user_link(user_id)).await {
+            Ok(link) => link,
+            Err(err) => {
+                println!("Failed to look up linked team for user {user_id}: {err}");
+                None
+            },
+        }
+    }
+
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        storage: Option<&Storage>,
+        config: &GuildConfig,
+    ) -> CreateInteractionResponseMessage {
+        let Some(storage) = storage else {
+            return CreateInteractionResponseMessage::new()
+                .content("Team links aren't available on this instance of the bot.")
+                .ephemeral(true);
+        };
+
+        let user_id = interaction.user.id.get();
+        let option = |name: &str| interaction.data.options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+
+        if let Some(CommandDataOptionValue::Boolean(true)) = option("unlink") {
+            let content = match storage::run(storage, move |storage| storage.remove_user_link(user_id)).await {
+                Ok(true) => "Your team has been unlinked.".to_string(),
+                Ok(false) => "You don't have a linked team.".to_string(),
+                Err(err) => format!("Failed to unlink your team.\n```rs\n{err:?}```"),
+            };
+
+            return CreateInteractionResponseMessage::new().content(content).ephemeral(true);
+        }
+
+        let Some(CommandDataOptionValue::String(team_number)) = option("number") else {
+            let content = match Self::linked_team(Some(storage), user_id).await {
+                Some(link) => format!("You're linked to **{}**. Commands like `/team` will use it when you leave out a team number.", link.team_number),
+                None => "You don't have a linked team. Use `/iam <number>` to link one.".to_string(),
+            };
+
+            return CreateInteractionResponseMessage::new().content(content).ephemeral(true);
+        };
+        let program_id_filter = match option("program") {
+            Some(CommandDataOptionValue::Integer(id)) => i32::try_from(*id).ok(),
+            _ => config.default_program,
+        };
+
+        let Ok(team) = TeamCommand::new(team_number.trim(), program_id_filter).find_robotevents_team(robotevents).await else {
+            return CreateInteractionResponseMessage::new()
+                .content("Failed to find a RobotEvents team with this number.")
+                .ephemeral(true);
+        };
+
+        let link = UserLink {
+            user_id,
+            team_id: team.id,
+            team_number: team.number.clone(),
+            program_id: team.program.id,
+        };
+
+        let content = match storage::run(storage, move |storage| storage.set_user_link(&link)).await {
+            Ok(()) => format!(
+                "Linked you to **{}** ({}). Commands like `/team` will now use it when you leave out a team number.",
+                team.number, team.team_name
+            ),
+            Err(err) => format!("Failed to link your team.\n```rs\n{err:?}```"),
+        };
+
+        CreateInteractionResponseMessage::new().content(content).ephemeral(true)
+    }
+}
\ No newline at end of file
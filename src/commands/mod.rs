@@ -2,8 +2,32 @@ pub mod ping;
 pub mod team;
 pub mod wiki;
 pub mod predict;
+pub mod event;
+pub mod projections;
+pub mod skills;
+pub mod progression;
+pub mod compare;
+pub mod follow;
+pub mod unfollow;
+pub mod iam;
+pub mod config;
+pub mod rule;
+pub mod qna;
+pub mod docs;
 
 pub use ping::*;
 pub use team::*;
 pub use wiki::*;
-pub use predict::*;
\ No newline at end of file
+pub use predict::*;
+pub use event::*;
+pub use projections::*;
+pub use skills::*;
+pub use progression::*;
+pub use compare::*;
+pub use follow::*;
+pub use unfollow::*;
+pub use iam::*;
+pub use config::*;
+pub use rule::*;
+pub use qna::*;
+pub use docs::*;
\ No newline at end of file
@@ -1,16 +1,32 @@
+use std::sync::Arc;
+
 use serenity::all::{
     CommandDataOptionValue, CommandOptionType
 };
 use serenity::builder::{
-    CreateCommand, CreateCommandOption, CreateEmbed,
-    CreateInteractionResponseMessage, CreateEmbedAuthor,
+    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed,
+    EditInteractionResponse, CreateEmbedAuthor,
     CreateEmbedFooter,
 };
 use serenity::client::Context;
 use serenity::model::application::CommandInteraction;
 use serenity::model::Color;
 
-use crate::api::vrc_data_analysis::VRCDataAnalysis;
+use robotevents::{
+    RobotEvents,
+    query::{PaginatedQuery, SeasonsQuery, TeamEventsQuery},
+    schema::{AllianceColor, Event, Match, MatchRound},
+};
+
+use crate::analytics::{self, trueskill::{self, TrueSkillEngine}, SeasonRatingsCache};
+use crate::api::vrc_data_analysis::{
+    VRCDataAnalysis,
+    schema::{CCWM, Prediction},
+};
+use crate::commands::{find_event_by_sku, EventCommand, IamCommand, TeamCommand};
+use crate::guild_config::GuildConfig;
+use crate::lookup_index::LookupIndex;
+use crate::storage::Storage;
 
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct PredictCommand;
@@ -18,53 +34,168 @@ pub struct PredictCommand;
 impl PredictCommand {
     pub fn command() -> CreateCommand {
         CreateCommand::new("predict")
-			.description("Predict the outcome of a VRC match. Use \"AVG\" to represent an average team.")
+			.description("Predict the outcome of a VRC or VEXU match. Use \"AVG\" to represent an average team.")
 			.add_option(
 				CreateCommandOption::new(CommandOptionType::String, "r1", "Red Alliance Partner 1")
-                    .required(true)
+                    .set_autocomplete(true)
 			)
             .add_option(
 				CreateCommandOption::new(CommandOptionType::String, "r2", "Red Alliance Partner 2")
-                    .required(true)
+                    .set_autocomplete(true)
 			)
             .add_option(
 				CreateCommandOption::new(CommandOptionType::String, "b1", "Blue Alliance Partner 1")
-                    .required(true)
+                    .set_autocomplete(true)
 			)
             .add_option(
 				CreateCommandOption::new(CommandOptionType::String, "b2", "Blue Alliance Partner 2")
-                    .required(true)
+                    .set_autocomplete(true)
+			)
+            .add_option(
+				CreateCommandOption::new(CommandOptionType::String, "event", "Predict a match from this event's schedule instead (e.g. RE-VRC-23-1234)")
+                    .set_autocomplete(true)
+			)
+            .add_option(
+				CreateCommandOption::new(CommandOptionType::String, "match", "Match name at the event (e.g. Q23, SF2-1, F1-1)")
+			)
+            .add_option(
+				CreateCommandOption::new(CommandOptionType::String, "team", "Predict this team's next unplayed match at the event (defaults to your team from /iam)")
+                    .set_autocomplete(true)
+			)
+            .add_option(
+				CreateCommandOption::new(CommandOptionType::String, "division", "Division name, for events with more than one division")
 			)
     }
 
+    /// Suggests event SKUs for the `event` option, and teams for every other option.
+    pub async fn autocomplete(
+        &self,
+        interaction: &CommandInteraction,
+        lookup_index: &LookupIndex,
+        storage: Option<&Storage>,
+        config: &GuildConfig,
+    ) -> CreateAutocompleteResponse {
+        match interaction.data.autocomplete().map(|opt| opt.name) {
+            Some("event") => EventCommand::sku_suggestions(interaction, lookup_index, config).await,
+            _ => TeamCommand::team_suggestions(interaction, lookup_index, storage).await,
+        }
+    }
+
     fn progress_bar(length: usize, progress: f64) -> String {
         let red_dot_count = (length as f64 * (progress / 100.0)).round() as usize;
 
         "🟥".repeat(red_dot_count) + "🟦".repeat(length - red_dot_count).as_ref()
     }
 
-    pub async fn response(
-        &self,
-        _ctx: &Context,
-        interaction: &CommandInteraction,
-        vrc_data_analysis: &VRCDataAnalysis
-    ) -> CreateInteractionResponseMessage {
+    /// Returns a rating engine for predicting a match locally.
+    ///
+    /// Teams are looked up in the guild's default program, and rated over the guild's default season if they
+    /// played in it (or their latest season otherwise). If every team's season has season-wide ratings built,
+    /// those are used. Otherwise, ratings are built by replaying every event that the given teams attended in
+    /// that season. Placeholder "AVG" teams are skipped, since they're given the default rating anyways.
+    async fn local_ratings(
+        robotevents: &RobotEvents,
+        season_ratings: &SeasonRatingsCache,
+        config: &GuildConfig,
+        team_numbers: &[&str],
+    ) -> Option<Arc<TrueSkillEngine>> {
         let mut teams = Vec::new();
-        for (idx, opt) in interaction.data.options.iter().enumerate() {
-            if let CommandDataOptionValue::String(number) = &opt.value {
-                teams.push(number);
-            } else {
-                return CreateInteractionResponseMessage::new()
-                    .add_embed(CreateEmbed::new().title(format!("Invalid team number at argument {}", idx + 1)));
+
+        for number in team_numbers.iter().filter(|number| !number.eq_ignore_ascii_case("AVG")) {
+            let team = TeamCommand::new(*number, config.default_program).find_robotevents_team(robotevents).await.ok()?;
+            let seasons = robotevents
+                .seasons(SeasonsQuery::new().team(team.id).per_page(250))
+                .await
+                .ok()?
+                .data;
+            let season = seasons
+                .iter()
+                .find(|season| {
+                    config.default_season
+                        .as_ref()
+                        .is_some_and(|years| format!("{}-{}", season.years_start, season.years_end) == *years)
+                })
+                .or(seasons.first())?;
+
+            teams.push((team, season.id));
+        }
+
+        if let Some((_, season_id)) = teams.first() {
+            if teams.iter().all(|(_, id)| id == season_id) {
+                if let Some(ratings) = season_ratings.get(*season_id).await {
+                    return Some(ratings);
+                }
             }
         }
 
-        let [r1, r2, b1, b2] = teams.as_slice() else {
-            return CreateInteractionResponseMessage::new().add_embed(CreateEmbed::new().title("Missing team argument."));
+        let mut events = Vec::new();
+        for (team, season_id) in teams.iter() {
+            events.extend(
+                team.events(robotevents, TeamEventsQuery::new().season(*season_id).per_page(250))
+                    .await
+                    .ok()?
+                    .data
+            );
+        }
+
+        trueskill::replay_events(robotevents, &events).await.ok().map(Arc::new)
+    }
+
+    /// Predicts a match using the local TrueSkill engine, for use when vrc-data-analysis is unavailable.
+    async fn local_prediction_embed(
+        robotevents: &RobotEvents,
+        season_ratings: &SeasonRatingsCache,
+        config: &GuildConfig,
+        red_alliance: &[&str],
+        blue_alliance: &[&str],
+    ) -> Option<CreateEmbed> {
+        let ratings = Self::local_ratings(robotevents, season_ratings, config, &[red_alliance, blue_alliance].concat()).await?;
+        let red_win_probability = ratings.win_probability(red_alliance, blue_alliance) * 100.0;
+
+        let prediction_msg = if red_win_probability >= 50.0 {
+            format!("Red alliance is predicted to win with a {red_win_probability:.1}% probability.")
+        } else {
+            format!("Blue alliance is predicted to win with a {:.1}% probability.", 100.0 - red_win_probability)
         };
 
-        let embed = match vrc_data_analysis.predict_match((r1, r2), (b1, b2)).await {
-            Ok(results) => CreateEmbed::new()
+        Some(CreateEmbed::new()
+            .author(CreateEmbedAuthor::new("Match Prediction Results"))
+            .title(format!("{} (🔴) vs {} (🔵)", red_alliance.join(" "), blue_alliance.join(" ")))
+            .description(format!("{}\n\n{}", prediction_msg, Self::progress_bar(17, red_win_probability)))
+            .footer(CreateEmbedFooter::new("vrc-data-analysis is unavailable, so this prediction was computed from RobotEvents match results."))
+            .color(if red_win_probability > 50.0 {
+                Color::from_rgb(210, 38, 48)
+            } else {
+                Color::from_rgb(0, 119, 200)
+            }))
+    }
+
+    /// Predicts a match between two alliances, preferring vrc-data-analysis and falling back to the local
+    /// TrueSkill engine if it errors.
+    ///
+    /// vrc-data-analysis only supports two-team alliances, so anything else goes straight to the local engine.
+    async fn prediction_embed(
+        robotevents: &RobotEvents,
+        vrc_data_analysis: &VRCDataAnalysis,
+        season_ratings: &SeasonRatingsCache,
+        config: &GuildConfig,
+        red_alliance: &[&str],
+        blue_alliance: &[&str],
+    ) -> CreateEmbed {
+        let ([r1, r2], [b1, b2]) = (red_alliance, blue_alliance) else {
+            return Self::local_prediction_embed(robotevents, season_ratings, config, red_alliance, blue_alliance)
+                .await
+                .unwrap_or(CreateEmbed::new().title("Failed to predict match from RobotEvents match results."));
+        };
+
+        // Alliance strength is requested alongside the TrueSkill prediction as a second opinion.
+        let (prediction, strength) = tokio::join!(
+            vrc_data_analysis.predict_match((r1, r2), (b1, b2)),
+            vrc_data_analysis.ccwm((r1, r2), (b1, b2)),
+        );
+
+        match prediction {
+            Ok(results) => Self::add_strength_fields(CreateEmbed::new()
                 .author(CreateEmbedAuthor::new("Match Prediction Results"))
                 .title(format!("{} {} (🔴) vs {} {} (🔵)", results.red1, results.red2, results.blue1, results.blue2))
                     .url("https://www.vrc-data-analysis.com/")
@@ -77,12 +208,303 @@ impl PredictCommand {
                     Color::from_rgb(210, 38, 48)
                 } else {
                     Color::from_rgb(0, 119, 200)
-                }),
-            Err(err) => CreateEmbed::new()
+                }), &results, strength.ok()),
+            Err(err) => match Self::local_prediction_embed(robotevents, season_ratings, config, red_alliance, blue_alliance).await {
+                Some(embed) => embed,
+                None => CreateEmbed::new()
                     .title("Failed to fetch match prediction data from vrc-data-analysis.")
                     .description(format!("```rs\n{err:?}```")),
+            },
+        }
+    }
+
+    /// Adds each alliance's CCWM strength and AWP rates from vrc-data-analysis to a prediction embed,
+    /// shown next to the TrueSkill win probability.
+    fn add_strength_fields(embed: CreateEmbed, prediction: &Prediction, strength: Option<CCWM>) -> CreateEmbed {
+        let Some(strength) = strength else {
+            return embed;
+        };
+
+        let mut embed = embed
+            .field(
+                "🔴 Red Alliance",
+                format!(
+                    "CCWM Strength: **{:.2}**\n{} AWP/Match: **{:.2}%**\n{} AWP/Match: **{:.2}%**",
+                    strength.red_strength,
+                    prediction.red1,
+                    strength.r1_awp_per_match * 100.0,
+                    prediction.red2,
+                    strength.r2_awp_per_match * 100.0,
+                ),
+                true,
+            )
+            .field(
+                "🔵 Blue Alliance",
+                format!(
+                    "CCWM Strength: **{:.2}**\n{} AWP/Match: **{:.2}%**\n{} AWP/Match: **{:.2}%**",
+                    strength.blue_strength,
+                    prediction.blue1,
+                    strength.b1_awp_per_match * 100.0,
+                    prediction.blue2,
+                    strength.b2_awp_per_match * 100.0,
+                ),
+                true,
+            );
+
+        if !strength.message.is_empty() {
+            embed = embed.field("Alliance Strength", strength.message, false);
+        }
+
+        embed
+    }
+
+    /// Returns the short code used for a match's round in match names (e.g. "Q" in "Q23").
+    fn round_code(round: i32) -> &'static str {
+        match analytics::match_round(round) {
+            Some(MatchRound::Practice) => "P",
+            Some(MatchRound::Qualification) => "Q",
+            Some(MatchRound::RoundOf16) => "R16",
+            Some(MatchRound::Quarterfinals) => "QF",
+            Some(MatchRound::Semifinals) => "SF",
+            Some(MatchRound::Finals) => "F",
+            None => "",
+        }
+    }
+
+    /// Returns the order that a match's round is played in at an event.
+    fn round_order(round: i32) -> i32 {
+        match analytics::match_round(round) {
+            Some(MatchRound::Practice) => 0,
+            Some(MatchRound::Qualification) => 1,
+            Some(MatchRound::RoundOf16) => 2,
+            Some(MatchRound::Quarterfinals) => 3,
+            Some(MatchRound::Semifinals) => 4,
+            Some(MatchRound::Finals) => 5,
+            None => 6,
+        }
+    }
+
+    /// Returns whether a team is on either alliance of a match.
+    fn plays_in(robotevents_match: &Match, team_number: &str) -> bool {
+        robotevents_match.alliances
+            .iter()
+            .any(|alliance| alliance.teams.iter().any(|t| t.team.name.eq_ignore_ascii_case(team_number)))
+    }
+
+    /// Parses a user-provided match name such as "Q23", "SF2-1" or "R16 3-1" into a round code, instance
+    /// and match number.
+    ///
+    /// A single number refers to the match number for practice and qualification matches, and to the
+    /// first match of that instance for elimination matches.
+    fn parse_match_name(name: &str) -> Option<(String, i32, i32)> {
+        let name = name
+            .trim_matches(|c| c == '<' || c == '>')
+            .to_uppercase()
+            .replace([' ', '#'], "");
+
+        let (code, numbers) = if let Some(numbers) = name.strip_prefix("R16") {
+            ("R16".to_string(), numbers.to_string())
+        } else {
+            let split_idx = name.find(|c: char| c.is_ascii_digit())?;
+            (name[..split_idx].to_string(), name[split_idx..].to_string())
+        };
+
+        if code.is_empty() {
+            return None;
+        }
+
+        let numbers = numbers
+            .split('-')
+            .map(|n| n.parse::<i32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        match (code.as_str(), numbers.as_slice()) {
+            ("P" | "Q", [matchnum]) => Some((code, 1, *matchnum)),
+            (_, [instance]) => Some((code, *instance, 1)),
+            (_, [instance, matchnum]) => Some((code, *instance, *matchnum)),
+            _ => None,
+        }
+    }
+
+    /// Finds a match at an event from its name, or the next unplayed match of a team if no name is given.
+    ///
+    /// Match names are only unique within a division, so the search is narrowed down to the given
+    /// division, or else to the division that the team plays in.
+    async fn find_scheduled_match(
+        robotevents: &RobotEvents,
+        sku: &str,
+        match_name: Option<&str>,
+        team_number: Option<&str>,
+        division_name: Option<&str>,
+    ) -> Result<(Event, Match), String> {
+        let event = find_event_by_sku(robotevents, sku)
+            .await
+            .map_err(|err| format!("Failed to fetch RobotEvents event data.\n```rs\n{err:?}```"))?
+            .ok_or("Failed to find a RobotEvents event with this SKU.".to_string())?;
+
+        let divisions = if let Some(division_name) = division_name {
+            let division = event.divisions
+                .iter()
+                .find(|division| division.name.eq_ignore_ascii_case(division_name.trim()) || division.id.to_string() == division_name.trim())
+                .ok_or(format!("Couldn't find a division named \"{division_name}\" at this event."))?;
+
+            vec![division.clone()]
+        } else {
+            event.divisions.clone()
         };
 
-        CreateInteractionResponseMessage::new().add_embed(embed)
+        let mut division_matches = Vec::new();
+        for division in divisions.iter() {
+            let matches = analytics::division_matches(robotevents, &event, division.id)
+                .await
+                .map_err(|err| format!("Failed to fetch RobotEvents match data.\n```rs\n{err:?}```"))?;
+
+            division_matches.push(matches);
+        }
+
+        if let Some(team_number) = team_number {
+            division_matches.retain(|matches| matches.iter().any(|m| Self::plays_in(m, team_number)));
+
+            if division_matches.is_empty() {
+                return Err(format!("{team_number} isn't scheduled to play any matches in this event or division."));
+            }
+        }
+
+        let found_match = if let Some(match_name) = match_name {
+            let (code, instance, matchnum) = Self::parse_match_name(match_name)
+                .ok_or(format!("Couldn't understand the match name \"{match_name}\". Try something like Q23 or SF2-1."))?;
+
+            let mut found_matches = division_matches
+                .into_iter()
+                .filter_map(|matches| {
+                    matches
+                        .into_iter()
+                        .find(|m| Self::round_code(m.round) == code && m.instance == instance && m.matchnum == matchnum)
+                })
+                .collect::<Vec<_>>();
+
+            if found_matches.len() > 1 {
+                return Err(format!("Match {match_name} exists in more than one division. Use the `division` or `team` option to pick one."));
+            }
+
+            found_matches
+                .pop()
+                .ok_or(format!("Couldn't find match {match_name} at this event."))?
+        } else if let Some(team_number) = team_number {
+            let mut matches = division_matches
+                .into_iter()
+                .flatten()
+                .filter(|m| !m.scored && Self::plays_in(m, team_number))
+                .collect::<Vec<_>>();
+
+            // Matches without a scheduled time go last, in the order they'd be played in.
+            matches.sort_by_key(|m| (m.scheduled.is_none(), m.scheduled.clone(), Self::round_order(m.round), m.instance, m.matchnum));
+
+            matches
+                .into_iter()
+                .next()
+                .ok_or(format!("{team_number} has no unplayed matches scheduled at this event."))?
+        } else {
+            return Err("Provide either a match name or a team number.".to_string());
+        };
+
+        Ok((event, found_match))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        vrc_data_analysis: &VRCDataAnalysis,
+        season_ratings: &SeasonRatingsCache,
+        storage: Option<&Storage>,
+        config: &GuildConfig,
+    ) -> EditInteractionResponse {
+        let string_option = |name: &str| {
+            interaction.data.options.iter().find(|opt| opt.name == name).and_then(|opt| {
+                if let CommandDataOptionValue::String(value) = &opt.value {
+                    Some(value.as_str())
+                } else {
+                    None
+                }
+            })
+        };
+
+        let embed = if let [Some(r1), Some(r2), Some(b1), Some(b2)] = ["r1", "r2", "b1", "b2"].map(string_option) {
+            Self::prediction_embed(robotevents, vrc_data_analysis, season_ratings, config, &[r1, r2], &[b1, b2]).await
+        } else if let Some(sku) = string_option("event") {
+            // Without a match name or team, predict the next match of the team that the user linked with `/iam`.
+            let match_name = string_option("match");
+            let linked_team = if match_name.is_none() && string_option("team").is_none() {
+                IamCommand::linked_team(storage, interaction.user.id.get()).await.map(|link| link.team_number)
+            } else {
+                None
+            };
+            let team_number = string_option("team").or(linked_team.as_deref());
+
+            let (event, scheduled_match) = match Self::find_scheduled_match(robotevents, sku, match_name, team_number, string_option("division")).await {
+                Ok(found) => found,
+                Err(message) => {
+                    return EditInteractionResponse::new()
+                        .add_embed(CreateEmbed::new().title("Failed to find match.").description(message));
+                },
+            };
+
+            let alliance_teams = |color: AllianceColor| {
+                scheduled_match.alliances
+                    .iter()
+                    .filter(|alliance| alliance.color == color)
+                    .flat_map(|alliance| alliance.teams.iter().filter(|t| !t.sitting).map(|t| t.team.name.as_str()))
+                    .collect::<Vec<_>>()
+            };
+
+            Self::prediction_embed(robotevents, vrc_data_analysis, season_ratings, config, &alliance_teams(AllianceColor::Red), &alliance_teams(AllianceColor::Blue))
+                .await
+                .author(CreateEmbedAuthor::new(format!("{} Prediction ({})", scheduled_match.name, event.name)))
+        } else {
+            return EditInteractionResponse::new().add_embed(
+                CreateEmbed::new()
+                    .title("Missing team argument.")
+                    .description("Provide all four teams, or an `event` to predict a scheduled match from.")
+            );
+        };
+
+        EditInteractionResponse::new().add_embed(embed)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str) -> Option<(String, i32, i32)> {
+        PredictCommand::parse_match_name(name)
+    }
+
+    #[test]
+    fn parses_qualification_matches() {
+        assert_eq!(parse("Q23"), Some(("Q".to_string(), 1, 23)));
+        assert_eq!(parse("q 23"), Some(("Q".to_string(), 1, 23)));
+        assert_eq!(parse("P4"), Some(("P".to_string(), 1, 4)));
+    }
+
+    #[test]
+    fn parses_elimination_matches() {
+        assert_eq!(parse("SF2-1"), Some(("SF".to_string(), 2, 1)));
+        assert_eq!(parse("QF3"), Some(("QF".to_string(), 3, 1)));
+        assert_eq!(parse("F1-2"), Some(("F".to_string(), 1, 2)));
+        assert_eq!(parse("R16 3-1"), Some(("R16".to_string(), 3, 1)));
+        assert_eq!(parse("R16 #3-1"), Some(("R16".to_string(), 3, 1)));
+    }
+
+    #[test]
+    fn rejects_invalid_match_names() {
+        assert_eq!(parse("23"), None);
+        assert_eq!(parse("Q"), None);
+        assert_eq!(parse("SF2-1-1"), None);
+        assert_eq!(parse("QFA"), None);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,302 @@
+use std::io::Cursor;
+use std::sync::Once;
+
+use chrono::{DateTime, Duration};
+use image::{ImageOutputFormat, RgbImage};
+use plotters::prelude::*;
+use plotters::style::{register_font, Color as _};
+use serenity::all::{CommandDataOptionValue, CommandOptionType};
+use serenity::builder::{
+    CreateAttachment, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+    EditInteractionResponse,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use robotevents::{
+    RobotEvents,
+    schema::{IdInfo, PaginatedResponse, Season},
+};
+
+use crate::api::skills::SkillsCache;
+use crate::api::skills_history::SkillsHistoryPoint;
+use crate::commands::TeamCommand;
+use crate::guild_config::GuildConfig;
+
+/// Dimensions of the rendered chart, in pixels.
+const CHART_SIZE: (u32, u32) = (1000, 800);
+
+/// File name that the chart is attached to the response as.
+const CHART_FILE_NAME: &str = "skills_progression.png";
+
+/// Font that chart text is drawn with. It's bundled with the bot so that charts render on hosts that don't
+/// have any system fonts installed.
+const CHART_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+
+static REGISTER_CHART_FONT: Once = Once::new();
+
+/// Handler for the "/progression" command.
+/// Charts how a team's world skills rank and best scores have changed over a season, using the
+/// leaderboard snapshots saved by [`SkillsCache`].
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ProgressionCommand;
+
+impl ProgressionCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command(program_list: Option<PaginatedResponse<IdInfo>>) -> CreateCommand {
+        let mut program_opt = CreateCommandOption::new(CommandOptionType::Integer, "program", "Program Name").required(false);
+        if let Some(program_list) = program_list {
+            for program in program_list.data.iter() {
+                program_opt = program_opt.add_int_choice(&program.name, program.id);
+            }
+        }
+
+        CreateCommand::new("progression")
+            .description("Charts a team's skills rank and scores over the season")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "number", "Team Number")
+                    .required(true)
+            )
+            .add_option(program_opt)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "season", "Season years (e.g. 2023-2024, defaults to the current season)")
+                    .required(false)
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Boolean, "post_season", "Chart post-season (e.g. Worlds) rankings instead of the regular season")
+                    .required(false)
+            )
+    }
+
+    /// Renders a team's skills history as a PNG, with world rank on the top half and best driver and
+    /// programming scores on the bottom half.
+    fn render_chart(title: &str, history: &[SkillsHistoryPoint]) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        REGISTER_CHART_FONT.call_once(|| {
+            assert!(register_font("sans-serif", FontStyle::Normal, CHART_FONT).is_ok(), "Bundled chart font is invalid.");
+        });
+
+        let points = history
+            .iter()
+            .map(|point| (DateTime::from_timestamp(point.taken_at, 0).unwrap_or_default(), point.entry))
+            .collect::<Vec<_>>();
+
+        // Charts need a non-empty time range, so a single snapshot is given a day on either side.
+        let (mut start, mut end) = (points[0].0, points[points.len() - 1].0);
+        if start == end {
+            start -= Duration::days(1);
+            end += Duration::days(1);
+        }
+
+        let worst_rank = points.iter().map(|(_, entry)| entry.rank).max().unwrap_or(1);
+        let best_score = points
+            .iter()
+            .map(|(_, entry)| entry.max_driver.max(entry.max_programming))
+            .max()
+            .unwrap_or_default();
+
+        let mut buffer = vec![0; (CHART_SIZE.0 * CHART_SIZE.1 * 3) as usize];
+        {
+            let root = BitMapBackend::with_buffer(&mut buffer, CHART_SIZE).into_drawing_area();
+            root.fill(&WHITE)?;
+            let root = root.titled(title, ("sans-serif", 28))?;
+            let areas = root.split_evenly((2, 1));
+
+            // Ranks are plotted as negative numbers so that first place ends up at the top of the chart.
+            let mut rank_chart = ChartBuilder::on(&areas[0])
+                .caption("World Rank", ("sans-serif", 20))
+                .margin(15)
+                .x_label_area_size(30)
+                .y_label_area_size(60)
+                .build_cartesian_2d(start..end, -(worst_rank + worst_rank / 10 + 1)..0)?;
+            rank_chart
+                .configure_mesh()
+                .x_label_formatter(&|time| time.format("%b %d").to_string())
+                .y_label_formatter(&|rank| format!("#{}", -rank))
+                .draw()?;
+            rank_chart.draw_series(LineSeries::new(
+                points.iter().map(|(time, entry)| (*time, -entry.rank)),
+                RED.stroke_width(2),
+            ))?;
+            rank_chart.draw_series(points.iter().map(|(time, entry)| Circle::new((*time, -entry.rank), 3, RED.filled())))?;
+
+            let mut score_chart = ChartBuilder::on(&areas[1])
+                .caption("Best Scores", ("sans-serif", 20))
+                .margin(15)
+                .x_label_area_size(30)
+                .y_label_area_size(60)
+                .build_cartesian_2d(start..end, 0..(best_score + best_score / 10 + 1))?;
+            score_chart
+                .configure_mesh()
+                .x_label_formatter(&|time| time.format("%b %d").to_string())
+                .draw()?;
+            score_chart
+                .draw_series(LineSeries::new(
+                    points.iter().map(|(time, entry)| (*time, entry.max_driver)),
+                    BLUE.stroke_width(2),
+                ))?
+                .label("Driver")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE));
+            score_chart
+                .draw_series(LineSeries::new(
+                    points.iter().map(|(time, entry)| (*time, entry.max_programming)),
+                    GREEN.stroke_width(2),
+                ))?
+                .label("Programming")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], GREEN));
+            score_chart
+                .configure_series_labels()
+                .position(SeriesLabelPosition::UpperLeft)
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()?;
+
+            root.present()?;
+        }
+
+        let image = RgbImage::from_raw(CHART_SIZE.0, CHART_SIZE.1, buffer).ok_or("Chart buffer has the wrong size.")?;
+        let mut png = Vec::new();
+        image.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+
+        Ok(png)
+    }
+
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        skills_cache: &SkillsCache,
+        season_list: Option<&PaginatedResponse<Season>>,
+        config: &GuildConfig,
+    ) -> EditInteractionResponse {
+        let option = |name: &str| interaction.data.options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+        let string_option = |name: &str| match option(name) {
+            Some(CommandDataOptionValue::String(value)) => Some(value.trim().to_string()),
+            _ => None,
+        };
+
+        let Some(history) = skills_cache.history() else {
+            return EditInteractionResponse::new().content("Skills history isn't being recorded on this instance of the bot.");
+        };
+
+        let Some(team_number) = string_option("number") else {
+            return EditInteractionResponse::new().content("Invalid team number.");
+        };
+        let program_id_filter = match option("program") {
+            Some(CommandDataOptionValue::Integer(id)) => i32::try_from(*id).ok(),
+            _ => config.default_program,
+        };
+        let post_season = matches!(option("post_season"), Some(CommandDataOptionValue::Boolean(true)));
+
+        let Ok(team) = TeamCommand::new(team_number, program_id_filter).find_robotevents_team(robotevents).await else {
+            return EditInteractionResponse::new().content("Failed to find a RobotEvents team with this number.");
+        };
+
+        let Some(season_list) = season_list else {
+            return EditInteractionResponse::new().content("Failed to fetch season list from RobotEvents.");
+        };
+
+        // Seasons are matched by their years, since season names change every year.
+        let season_years = string_option("season").or(config.default_season.clone());
+        let Some(season) = season_list.data
+            .iter()
+            .filter(|season| season.program.id == team.program.id)
+            .filter(|season| match &season_years {
+                Some(years) => format!("{}-{}", season.years_start, season.years_end) == *years,
+                None => true,
+            })
+            .max_by_key(|season| season.years_start)
+        else {
+            return EditInteractionResponse::new().content("Failed to find a season matching these options.");
+        };
+
+        let grade = team.grade.to_string();
+
+        // Make sure that the leaderboard is being tracked, so that the team's current standing shows up even
+        // if nobody has looked at it before. History is still shown if this fails.
+        if let Err(err) = skills_cache.get_rankings(season.id, &grade, post_season, robotevents).await {
+            println!("Failed to fetch skills leaderboard for progression chart: {err}");
+        }
+
+        let points = match history.team_history(team.id, season.id, &grade, post_season).await {
+            Ok(points) => points,
+            Err(err) => {
+                return EditInteractionResponse::new().add_embed(
+                    CreateEmbed::new()
+                        .title("Failed to read skills history.")
+                        .description(format!("```rs\n{err:?}```")),
+                );
+            },
+        };
+
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return EditInteractionResponse::new().content("No skills runs have been recorded for this team yet.");
+        };
+
+        let title = if post_season {
+            format!("{} Post-Season Skills Progression", team.number)
+        } else {
+            format!("{} Skills Progression", team.number)
+        };
+
+        // Rendering is CPU-bound, so it's kept off the async runtime.
+        let chart = {
+            let (title, points) = (title.clone(), points.clone());
+            tokio::task::spawn_blocking(move || Self::render_chart(&title, &points))
+                .await
+                .unwrap_or_else(|err| Err(err.into()))
+        };
+        let chart = match chart {
+            Ok(chart) => chart,
+            Err(err) => {
+                return EditInteractionResponse::new().add_embed(
+                    CreateEmbed::new()
+                        .title("Failed to render skills progression chart.")
+                        .description(format!("```rs\n{err:?}```")),
+                );
+            },
+        };
+
+        let embed = CreateEmbed::new()
+            .title(title)
+            .description(format!(
+                "World Ranking: **#{}** → **#{}**\nDriver: **{}** → **{}**\nProgramming: **{}** → **{}**",
+                first.entry.rank,
+                last.entry.rank,
+                first.entry.max_driver,
+                last.entry.max_driver,
+                first.entry.max_programming,
+                last.entry.max_programming,
+            ))
+            .image(format!("attachment://{CHART_FILE_NAME}"))
+            .footer(CreateEmbedFooter::new(format!("{} | {} Recorded Changes", season.name, points.len())))
+            .color(match team.program.id {
+                1 | 4 => Color::from_rgb(210, 38, 48), // VRC/VEXU
+                41 => Color::from_rgb(0, 119, 200), // VIQC
+                57 => Color::from_rgb(91, 91, 91), // VAIC
+                _ => Default::default(),
+            });
+
+        EditInteractionResponse::new()
+            .add_embed(embed)
+            .new_attachment(CreateAttachment::bytes(chart, CHART_FILE_NAME))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::skills_history::SkillsSnapshotEntry;
+
+    #[test]
+    fn renders_chart_with_bundled_font() {
+        let history = [(1_700_000_000, 120, 40, 20), (1_700_600_000, 45, 80, 55)].map(|(taken_at, rank, max_driver, max_programming)| {
+            SkillsHistoryPoint {
+                taken_at,
+                entry: SkillsSnapshotEntry { team_id: 1, rank, max_driver, max_programming, score: max_driver + max_programming },
+            }
+        });
+
+        let png = ProgressionCommand::render_chart("229V Skills Progression", &history).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
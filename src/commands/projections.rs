@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use serenity::all::{
+    CommandDataOptionValue, CommandOptionType
+};
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed,
+    EditInteractionResponse, CreateEmbedFooter,
+};
+use serenity::client::Context;
+use serenity::futures::{stream, StreamExt};
+use serenity::model::application::CommandInteraction;
+use serenity::model::Color;
+
+use robotevents::{
+    RobotEvents,
+    query::{DivisionRankingsQuery, PaginatedQuery},
+};
+
+use crate::analytics::{self, Rating, TrueSkillEngine};
+use crate::api::vrc_data_analysis::VRCDataAnalysis;
+use crate::commands::{find_event_by_sku, robotevents_event_url};
+
+/// Number of times the remaining qualification schedule is simulated.
+const ITERATIONS: usize = 5000;
+
+/// Maximum number of vrc-data-analysis requests in flight at once while seeding ratings, so that a large
+/// division doesn't flood the API.
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Discord caps embed descriptions at 4096 characters, so long lists are cut off a bit before that.
+const MAX_DESCRIPTION_LENGTH: usize = 4000;
+
+/// Handler for the "/projections" command.
+/// Runs a Monte Carlo simulation of an event division's remaining qualification matches to project
+/// where each team will finish in the rankings.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct ProjectionsCommand;
+
+impl ProjectionsCommand {
+    pub fn command() -> CreateCommand {
+        CreateCommand::new("projections")
+            .description("Project the final qualification rankings of an event from its match schedule")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "event", "Event SKU (e.g. RE-VRC-23-1234)")
+                    .required(true)
+                    .set_autocomplete(true)
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "division", "Division name (defaults to the first division)")
+                    .required(false)
+            )
+    }
+
+    /// Seeds the rating engine used to decide simulated matches.
+    ///
+    /// VRC teams are seeded with their season-wide ratings from vrc-data-analysis where possible. Teams that
+    /// vrc-data-analysis doesn't know about keep the default rating. Returns the engine along with whether any
+    /// team was seeded; if none were (other programs, or vrc-data-analysis is unavailable), the caller should
+    /// build ratings from the matches that have already been played in the division instead.
+    async fn seed_ratings(
+        vrc_data_analysis: &VRCDataAnalysis,
+        program_id: i32,
+        matches: &[robotevents::schema::Match],
+    ) -> (TrueSkillEngine, bool) {
+        let mut engine = TrueSkillEngine::default();
+        let mut seeded_remotely = false;
+
+        if program_id == 1 {
+            let mut team_numbers = matches
+                .iter()
+                .flat_map(|m| m.alliances.iter())
+                .flat_map(|alliance| alliance.teams.iter().map(|t| t.team.name.clone()))
+                .collect::<Vec<_>>();
+            team_numbers.sort();
+            team_numbers.dedup();
+
+            // The futures are collected up front so the closure's borrows don't end up in the stream's type,
+            // which would keep the handler future from being `Send`.
+            let requests = team_numbers
+                .iter()
+                .map(|number| async move { (number, vrc_data_analysis.team_info(number).await) })
+                .collect::<Vec<_>>();
+            let team_info = stream::iter(requests)
+                .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+                .collect::<Vec<_>>()
+                .await;
+
+            for (number, info) in team_info {
+                let Ok(info) = info else {
+                    continue;
+                };
+
+                engine.set_rating(number.clone(), Rating {
+                    mu: info.mu,
+                    sigma: info.sigma,
+                    matches_played: (info.total_wins + info.total_losses + info.total_ties) as usize,
+                });
+                seeded_remotely = true;
+            }
+        }
+
+        (engine, seeded_remotely)
+    }
+
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        vrc_data_analysis: &VRCDataAnalysis,
+    ) -> EditInteractionResponse {
+        let string_option = |name: &str| {
+            interaction.data.options.iter().find(|opt| opt.name == name).and_then(|opt| {
+                if let CommandDataOptionValue::String(value) = &opt.value {
+                    Some(value.trim())
+                } else {
+                    None
+                }
+            })
+        };
+
+        let Some(sku) = string_option("event") else {
+            return EditInteractionResponse::new().content("Invalid event SKU.");
+        };
+
+        let event = match find_event_by_sku(robotevents, sku).await {
+            Ok(Some(event)) => event,
+            Ok(None) => return EditInteractionResponse::new().content("Failed to find a RobotEvents event with this SKU."),
+            Err(err) => {
+                return EditInteractionResponse::new().add_embed(
+                    CreateEmbed::new()
+                        .title("Failed to fetch RobotEvents event data.")
+                        .description(format!("```rs\n{err:?}```")),
+                );
+            },
+        };
+
+        let division = match string_option("division") {
+            Some(name) => event.divisions.iter().find(|division| division.name.eq_ignore_ascii_case(name)),
+            None => event.divisions.first(),
+        };
+        let Some(division) = division else {
+            return EditInteractionResponse::new().content("Failed to find a division with this name at the event.");
+        };
+
+        let matches = match analytics::division_matches(robotevents, &event, division.id).await {
+            Ok(matches) => matches,
+            Err(err) => {
+                return EditInteractionResponse::new().add_embed(
+                    CreateEmbed::new()
+                        .title("Failed to fetch RobotEvents match data.")
+                        .description(format!("```rs\n{err:?}```")),
+                );
+            },
+        };
+
+        // Autonomous points can't be simulated, so each team's current AP is carried over from the rankings.
+        let autonomous_points = match event.division_rankings(division.id, robotevents, DivisionRankingsQuery::new().per_page(250)).await {
+            Ok(rankings) => rankings.data
+                .into_iter()
+                .map(|ranking| (ranking.team.name, ranking.ap))
+                .collect::<HashMap<_, _>>(),
+            Err(err) => {
+                return EditInteractionResponse::new().add_embed(
+                    CreateEmbed::new()
+                        .title("Failed to fetch RobotEvents rankings data.")
+                        .description(format!("```rs\n{err:?}```")),
+                );
+            },
+        };
+
+        let (mut ratings, seeded_remotely) = Self::seed_ratings(vrc_data_analysis, event.program.id, &matches).await;
+
+        // Replaying matches and running thousands of simulations is CPU-heavy, so keep it off the async runtime.
+        let projection = tokio::task::spawn_blocking(move || {
+            if !seeded_remotely {
+                ratings.replay(&matches);
+            }
+
+            analytics::project_rankings(&matches, &ratings, &autonomous_points, ITERATIONS)
+        }).await;
+
+        let projection = match projection {
+            Ok(Some(projection)) => projection,
+            Ok(None) => {
+                return EditInteractionResponse::new().add_embed(
+                    CreateEmbed::new()
+                        .title(format!("{} Projected Rankings", event.name))
+                        .url(robotevents_event_url(&event.program, &event.sku))
+                        .description("The qualification schedule for this division hasn't been published yet."),
+                );
+            },
+            Err(err) => {
+                return EditInteractionResponse::new().add_embed(
+                    CreateEmbed::new()
+                        .title("Failed to simulate the remaining matches.")
+                        .description(format!("```rs\n{err:?}```")),
+                );
+            },
+        };
+
+        let mut description = if projection.remaining_matches == 0 {
+            "*All qualification matches have been played, so these are the final rankings.*\n\n".to_string()
+        } else {
+            format!(
+                "Based on {} simulations of the {} remaining qualification matches.\n\n",
+                projection.iterations, projection.remaining_matches
+            )
+        };
+
+        for (idx, team) in projection.teams.iter().enumerate() {
+            let (low, high) = (team.rank_percentile(0.1), team.rank_percentile(0.9));
+            let line = format!(
+                "**{}** avg **#{:.1}** (#{}-#{}) | Top 8: **{:.0}%** | Top 16: **{:.0}%**\n",
+                team.team,
+                team.average_rank,
+                low,
+                high,
+                team.top_8 * 100.0,
+                team.top_16 * 100.0,
+            );
+
+            if description.len() + line.len() > MAX_DESCRIPTION_LENGTH {
+                description.push_str(&format!("*...and {} more.*", projection.teams.len() - idx));
+                break;
+            }
+            description.push_str(&line);
+        }
+
+        let embed = CreateEmbed::new()
+            .title(if event.divisions.len() > 1 {
+                format!("{} Projected Rankings ({})", event.name, division.name)
+            } else {
+                format!("{} Projected Rankings", event.name)
+            })
+            .url(robotevents_event_url(&event.program, &event.sku))
+            .description(description)
+            .footer(CreateEmbedFooter::new(if seeded_remotely {
+                "Rank ranges cover 80% of simulations. Ratings provided by vrc-data-analysis.com (unrated teams start at the default rating)."
+            } else {
+                "Rank ranges cover 80% of simulations. Ratings computed from this division's played matches."
+            }))
+            .color(match event.program.id {
+                1 | 4 => Color::from_rgb(210, 38, 48), // VRC/VEXU
+                41 => Color::from_rgb(0, 119, 200), // VIQC
+                57 => Color::from_rgb(91, 91, 91), // VAIC
+                _ => Default::default(),
+            });
+
+        EditInteractionResponse::new().add_embed(embed)
+    }
+}
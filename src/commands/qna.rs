@@ -0,0 +1,98 @@
+use serenity::all::{CommandDataOptionValue, CommandOptionType};
+use serenity::builder::{
+    CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponseMessage,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use crate::qna::{excerpt, QnaArchive};
+
+/// Number of results shown when the `results` option is left out.
+const DEFAULT_RESULTS: usize = 3;
+
+/// Lengths (in characters) of the question and answer excerpts shown for each result.
+const QUESTION_EXCERPT_LENGTH: usize = 200;
+const ANSWER_EXCERPT_LENGTH: usize = 400;
+
+/// Handler for the "/qna" command.
+/// Searches an archive of answered questions from the official Q&A.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct QnaCommand;
+
+impl QnaCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command() -> CreateCommand {
+        CreateCommand::new("qna")
+            .description("Searches answered questions from the official Q&A")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "query", "What to search for (e.g. SG3 expansion)")
+                    .required(true)
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "results", "Number of results to show")
+                    .min_int_value(1)
+                    .max_int_value(5)
+                    .required(false)
+            )
+    }
+
+    pub fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        qna_archive: Option<&QnaArchive>,
+    ) -> CreateInteractionResponseMessage {
+        let option = |name: &str| interaction.data.options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+
+        let Some(archive) = qna_archive else {
+            return CreateInteractionResponseMessage::new().content("The Q&A archive isn't available on this instance of the bot.");
+        };
+
+        let query = match option("query") {
+            Some(CommandDataOptionValue::String(query)) => query.trim(),
+            _ => return CreateInteractionResponseMessage::new().content("Invalid search query."),
+        };
+        let result_count = match option("results") {
+            Some(CommandDataOptionValue::Integer(count)) => usize::try_from(*count).unwrap_or(DEFAULT_RESULTS),
+            _ => DEFAULT_RESULTS,
+        };
+
+        let snapshot = archive.snapshot();
+        let matches = archive.search(query, result_count);
+
+        if matches.is_empty() {
+            return CreateInteractionResponseMessage::new()
+                .content(format!("Couldn't find any {} Q&A answers matching \"{query}\".", snapshot.game));
+        }
+
+        let mut embed = CreateEmbed::new()
+            .title(format!("Q&A results for \"{query}\""))
+            .footer(CreateEmbedFooter::new(match &snapshot.exported_at {
+                Some(exported_at) => format!("{} Q&A ({}) | Archived {exported_at}", snapshot.game, snapshot.season),
+                None => format!("{} Q&A ({})", snapshot.game, snapshot.season),
+            }))
+            .color(Color::from_rgb(210, 38, 48));
+
+        for result in matches {
+            let entry = result.entry;
+            let answered = match &entry.answered_at {
+                Some(date) => format!("Answered {date}"),
+                None => "Answered".to_string(),
+            };
+
+            // Field names are limited to 256 characters.
+            embed = embed.field(
+                format!("Q{}: {}", entry.id, entry.title).chars().take(256).collect::<String>(),
+                format!(
+                    "> {}\n{}\n*{answered}* | [View on RobotEvents]({})",
+                    excerpt(&entry.question, query, QUESTION_EXCERPT_LENGTH),
+                    excerpt(&entry.answer, query, ANSWER_EXCERPT_LENGTH),
+                    entry.url
+                ),
+                false,
+            );
+        }
+
+        CreateInteractionResponseMessage::new().add_embed(embed)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,109 @@
+use serenity::all::{CommandDataOptionValue, CommandOptionType};
+use serenity::builder::{
+    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponseMessage,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use crate::guild_config::GuildConfig;
+use crate::rules::{RuleBook, RuleLibrary};
+
+/// Discord won't show more than 25 autocomplete choices.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+/// Handler for the "/rule" command.
+/// Looks up a rule from one of the game manuals imported into the bot's [`RuleLibrary`].
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct RuleCommand;
+
+impl RuleCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command(rules: &RuleLibrary) -> CreateCommand {
+        let mut season_opt = CreateCommandOption::new(CommandOptionType::String, "season", "Season (defaults to the current game)").required(false);
+        for book in rules.books() {
+            season_opt = season_opt.add_string_choice(format!("{} ({})", book.game, book.season), &book.season);
+        }
+
+        CreateCommand::new("rule")
+            .description("Looks up a rule from the game manual")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "id", "Rule ID (e.g. SG3 or <R12>)")
+                    .required(true)
+                    .set_autocomplete(true)
+            )
+            .add_option(season_opt)
+    }
+
+    /// Picks the rule book for a command, using the server's default season if none was given.
+    fn rule_book<'a>(interaction: &CommandInteraction, rules: &'a RuleLibrary, config: &GuildConfig) -> Option<&'a RuleBook> {
+        let season = interaction.data.options
+            .iter()
+            .find(|opt| opt.name == "season")
+            .and_then(|opt| opt.value.as_str())
+            .map(|season| season.to_string())
+            .or(config.default_season.clone());
+
+        // Servers may default to a season that has no imported rules, in which case the newest game is used.
+        rules.for_season(season.as_deref()).or(rules.for_season(None))
+    }
+
+    /// Suggests rules matching the `id` option as the user types it.
+    pub fn autocomplete(&self, interaction: &CommandInteraction, rules: &RuleLibrary, config: &GuildConfig) -> CreateAutocompleteResponse {
+        let query = interaction.data.autocomplete().map(|opt| opt.value).unwrap_or_default();
+        let Some(book) = Self::rule_book(interaction, rules, config) else {
+            return CreateAutocompleteResponse::new();
+        };
+
+        book.search(query, MAX_AUTOCOMPLETE_CHOICES)
+            .into_iter()
+            .fold(CreateAutocompleteResponse::new(), |response, rule| {
+                // Choice names are limited to 100 characters.
+                let name = format!("{} {}", rule.tag(), rule.title).chars().take(100).collect::<String>();
+                response.add_string_choice(name, &rule.id)
+            })
+    }
+
+    pub fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        rules: &RuleLibrary,
+        config: &GuildConfig,
+    ) -> CreateInteractionResponseMessage {
+        let id = match interaction.data.options.iter().find(|opt| opt.name == "id").map(|opt| &opt.value) {
+            Some(CommandDataOptionValue::String(id)) => id.trim(),
+            _ => return CreateInteractionResponseMessage::new().content("Invalid rule ID."),
+        };
+
+        let Some(book) = Self::rule_book(interaction, rules, config) else {
+            return CreateInteractionResponseMessage::new().content("No game manuals are available.");
+        };
+
+        let Some(rule) = book.rule(id) else {
+            let suggestions = book.search(id, 5).iter().map(|rule| format!("`{}` {}", rule.tag(), rule.title)).collect::<Vec<_>>();
+
+            return CreateInteractionResponseMessage::new().content(if suggestions.is_empty() {
+                format!("Couldn't find rule `{id}` in the {} game manual.", book.game)
+            } else {
+                format!("Couldn't find rule `{id}` in the {} game manual. Did you mean:\n{}", book.game, suggestions.join("\n"))
+            });
+        };
+
+        let mut embed = CreateEmbed::new()
+            .title(format!("{} {}", rule.tag(), rule.title))
+            .url(book.manual_link(rule))
+            .description(&rule.text)
+            .footer(CreateEmbedFooter::new(format!("{} Game Manual ({}) | Version: {}", book.game, book.season, book.version)))
+            .color(Color::from_rgb(210, 38, 48));
+
+        if let Some(section) = &rule.section {
+            embed = embed.field("Section", section, true);
+        }
+        if let Some(violation) = &rule.violation {
+            embed = embed.field("Violation", violation, false);
+        }
+
+        CreateInteractionResponseMessage::new().add_embed(embed)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,356 @@
+use serenity::all::{
+    ButtonStyle, CommandDataOptionValue, CommandOptionType, ComponentInteraction,
+    ComponentInteractionDataKind,
+};
+use serenity::builder::{
+    CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
+    CreateInteractionResponseFollowup, EditInteractionResponse, CreateEmbedFooter,
+};
+use serenity::client::Context;
+use serenity::model::{application::CommandInteraction, Color};
+
+use robotevents::{
+    RobotEvents,
+    schema::{PaginatedResponse, Season, IdInfo},
+};
+use crate::api::skills::{SkillsCache, SkillsRanking};
+use crate::guild_config::GuildConfig;
+
+/// Number of leaderboard entries shown per embed.
+const RANKINGS_PER_PAGE: usize = 15;
+
+/// Grade levels that RobotEvents skills leaderboards are split by.
+const GRADES: [&str; 4] = ["High School", "Middle School", "College", "Elementary School"];
+
+/// Builds a pair of buttons for switching between regular season and post-season skills rankings.
+///
+/// Button IDs are `{prefix}_regular_season` and `{prefix}_post_season`, with the currently selected
+/// option highlighted and disabled.
+pub fn season_type_buttons(prefix: &str, post_season: bool) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{prefix}_regular_season"))
+            .label("Regular Season")
+            .style(if post_season { ButtonStyle::Secondary } else { ButtonStyle::Primary })
+            .disabled(!post_season),
+        CreateButton::new(format!("{prefix}_post_season"))
+            .label("Post-Season")
+            .style(if post_season { ButtonStyle::Primary } else { ButtonStyle::Secondary })
+            .disabled(post_season),
+    ])
+}
+
+/// Handler for the "/skills" command.
+/// The skills command pages through a season's cached world skills leaderboard, optionally filtered
+/// down to a single country or region.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct SkillsCommand {
+    /// Season that the leaderboard is for.
+    season: Option<Season>,
+
+    /// Grade level that the leaderboard is for.
+    grade: String,
+
+    /// Whether the leaderboard is for post-season events (such as Worlds) rather than the regular season.
+    post_season: bool,
+
+    /// Country that the leaderboard is filtered to.
+    country: Option<String>,
+
+    /// Region that the leaderboard is filtered to.
+    region: Option<String>,
+
+    /// Leaderboard entries remaining after applying the user's filters.
+    rankings: Vec<SkillsRanking>,
+
+    /// Human-readable description of the filters applied to [`Self::rankings`].
+    filter_description: Option<String>,
+
+    /// Current page of the leaderboard being viewed, starting at zero.
+    page: usize,
+}
+
+impl SkillsCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command(program_list: Option<PaginatedResponse<IdInfo>>) -> CreateCommand {
+        let mut program_opt = CreateCommandOption::new(CommandOptionType::Integer, "program", "Program Name (defaults to VRC)").required(false);
+        if let Some(program_list) = program_list {
+            for program in program_list.data.iter() {
+                program_opt = program_opt.add_int_choice(&program.name, program.id);
+            }
+        }
+
+        let mut grade_opt = CreateCommandOption::new(CommandOptionType::String, "grade", "Grade Level (defaults to Middle School for VIQC, College for VEXU, or else High School)").required(false);
+        for grade in GRADES {
+            grade_opt = grade_opt.add_string_choice(grade, grade);
+        }
+
+        CreateCommand::new("skills")
+            .description("Displays the world skills leaderboard")
+            .add_option(grade_opt)
+            .add_option(program_opt)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "season", "Season years (e.g. 2023-2024, defaults to the current season)")
+                    .required(false)
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "country", "Only show teams from this country")
+                    .required(false)
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "region", "Only show teams from this region (e.g. a state or province)")
+                    .required(false)
+            )
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::Boolean, "post_season", "Show post-season (e.g. Worlds) rankings instead of the regular season")
+                    .required(false)
+            )
+    }
+
+    /// Returns the number of pages in the filtered leaderboard.
+    fn page_count(&self) -> usize {
+        self.rankings.len().div_ceil(RANKINGS_PER_PAGE).max(1)
+    }
+
+    /// Generate the regular season/post-season toggle, along with page buttons if the leaderboard spans
+    /// more than one page.
+    pub fn components(&self) -> Vec<CreateActionRow> {
+        let mut components = vec![season_type_buttons("skills", self.post_season)];
+
+        if self.page_count() > 1 {
+            components.push(CreateActionRow::Buttons(vec![
+                CreateButton::new("skills_previous")
+                    .label("Previous")
+                    .style(ButtonStyle::Secondary)
+                    .disabled(self.page == 0),
+                CreateButton::new("skills_next")
+                    .label("Next")
+                    .style(ButtonStyle::Secondary)
+                    .disabled(self.page + 1 >= self.page_count()),
+            ]));
+        }
+
+        components
+    }
+
+    /// Returns the grade level that a program's leaderboard is shown for when no grade is given.
+    fn default_grade(program_id: i32) -> &'static str {
+        match program_id {
+            4 => "College", // VEXU
+            41 => "Middle School", // VIQC
+            _ => "High School",
+        }
+    }
+
+    /// Constructs an embed for the current page of the leaderboard.
+    pub fn embed(&self) -> CreateEmbed {
+        let season_name = self.season.as_ref().map(|season| season.name.as_str()).unwrap_or("Unknown Season");
+
+        let mut embed = CreateEmbed::new()
+            .title(if self.post_season {
+                format!("{} Post-Season Skills Leaderboard", self.grade)
+            } else {
+                format!("{} Skills Leaderboard", self.grade)
+            })
+            .color(match self.season.as_ref().map(|season| season.program.id) {
+                Some(1 | 4) => Color::from_rgb(210, 38, 48), // VRC/VEXU
+                Some(41) => Color::from_rgb(0, 119, 200), // VIQC
+                Some(57) => Color::from_rgb(91, 91, 91), // VAIC
+                _ => Default::default(),
+            })
+            .footer(CreateEmbedFooter::new(format!(
+                "{} | Page {} of {} ({} Teams)",
+                season_name,
+                self.page + 1,
+                self.page_count(),
+                self.rankings.len()
+            )));
+
+        let mut description = match &self.filter_description {
+            Some(filter) => format!("*{filter}*\n\n"),
+            None => String::new(),
+        };
+
+        if self.rankings.is_empty() {
+            description.push_str("No skills runs found.");
+        }
+
+        for ranking in self.rankings.iter().skip(self.page * RANKINGS_PER_PAGE).take(RANKINGS_PER_PAGE) {
+            description.push_str(&format!(
+                "**#{}** {} ({})\nDriver: **{}** | Programming: **{}** | Combined: **{}**\n",
+                ranking.rank,
+                ranking.team.team,
+                ranking.team.team_name,
+                ranking.scores.max_driver,
+                ranking.scores.max_programming,
+                ranking.scores.score
+            ));
+        }
+
+        embed = embed.description(description);
+
+        embed
+    }
+
+    /// Generate a response to a command interaction, sent as an edit to the deferred response since
+    /// downloading a leaderboard that isn't cached yet can take longer than Discord's 3 second deadline.
+    ///
+    /// All arguments to `/skills` are optional. By default, this shows the High School VRC leaderboard for
+    /// the current season. Programs without a High School division default to their own grade level
+    /// instead (e.g. Middle School for VIQC).
+    pub async fn response(
+        &mut self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        robotevents: &RobotEvents,
+        skills_cache: &SkillsCache,
+        season_list: Option<&PaginatedResponse<Season>>,
+        config: &GuildConfig,
+    ) -> EditInteractionResponse {
+        let option = |name: &str| interaction.data.options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+        let string_option = |name: &str| match option(name) {
+            Some(CommandDataOptionValue::String(value)) => Some(value.trim().to_string()),
+            _ => None,
+        };
+
+        let program_id = match option("program") {
+            Some(CommandDataOptionValue::Integer(id)) => i32::try_from(*id).unwrap_or(1),
+            _ => config.default_program.unwrap_or(1),
+        };
+        self.grade = string_option("grade").unwrap_or(Self::default_grade(program_id).to_string());
+
+        self.post_season = matches!(option("post_season"), Some(CommandDataOptionValue::Boolean(true)));
+
+        let Some(season_list) = season_list else {
+            return EditInteractionResponse::new().content("Failed to fetch season list from RobotEvents.");
+        };
+
+        // Seasons are matched by their years, since season names change every year.
+        let season_years = string_option("season").or(config.default_season.clone());
+        self.season = season_list.data
+            .iter()
+            .filter(|season| season.program.id == program_id)
+            .filter(|season| match &season_years {
+                Some(years) => format!("{}-{}", season.years_start, season.years_end) == *years,
+                None => true,
+            })
+            .max_by_key(|season| season.years_start)
+            .cloned();
+
+        if self.season.is_none() {
+            return EditInteractionResponse::new().content("Failed to find a season matching these options.");
+        }
+
+        self.country = string_option("country");
+        self.region = string_option("region");
+        self.filter_description = match (&self.country, &self.region) {
+            (Some(country), Some(region)) => Some(format!("Teams from {region}, {country}")),
+            (Some(filter), None) | (None, Some(filter)) => Some(format!("Teams from {filter}")),
+            (None, None) => None,
+        };
+
+        if let Err(err) = self.load_rankings(robotevents, skills_cache).await {
+            return EditInteractionResponse::new().add_embed(
+                CreateEmbed::new()
+                    .title("Failed to get skills ranking data from RobotEvents.")
+                    .description(format!("```rs\n{err:?}```")),
+            );
+        }
+
+        EditInteractionResponse::new()
+            .embed(self.embed())
+            .components(self.components())
+    }
+
+    /// Fetches the leaderboard for the selected season, grade and season type from the cache, and applies
+    /// the user's filters to it.
+    async fn load_rankings(&mut self, robotevents: &RobotEvents, skills_cache: &SkillsCache) -> Result<(), reqwest::Error> {
+        let Some(season) = &self.season else {
+            return Ok(());
+        };
+
+        let rankings = skills_cache.get_rankings(season.id, &self.grade, self.post_season, robotevents).await?;
+
+        self.rankings = rankings
+            .iter()
+            .filter(|ranking| match &self.country {
+                Some(country) => ranking.team.country.eq_ignore_ascii_case(country),
+                None => true,
+            })
+            .filter(|ranking| match &self.region {
+                Some(region) => {
+                    ranking.team.event_region.eq_ignore_ascii_case(region)
+                        || ranking.team.region.as_ref().is_some_and(|r| r.eq_ignore_ascii_case(region))
+                },
+                None => true,
+            })
+            .cloned()
+            .collect();
+        self.page = 0;
+
+        Ok(())
+    }
+
+    /// Event handler for user interactions with a message component (the page and season type buttons).
+    ///
+    /// The interaction is acknowledged straight away and the original message is edited once the new page
+    /// is ready, since switching season types may have to download a leaderboard. Returns a followup message
+    /// to send if something went wrong.
+    pub async fn component_interaction_response(
+        &mut self,
+        ctx: &Context,
+        command_interaction: &CommandInteraction,
+        component_interaction: &ComponentInteraction,
+        robotevents: &RobotEvents,
+        skills_cache: &SkillsCache,
+    ) -> Option<CreateInteractionResponseFollowup> {
+        if component_interaction.defer(&ctx).await.is_err() {
+            return None;
+        }
+
+        if let ComponentInteractionDataKind::Button = &component_interaction.data.kind {
+            match component_interaction.data.custom_id.as_str() {
+                "skills_previous" => self.page = self.page.saturating_sub(1),
+                "skills_next" => self.page = (self.page + 1).min(self.page_count() - 1),
+                "skills_regular_season" | "skills_post_season" => {
+                    self.post_season = component_interaction.data.custom_id == "skills_post_season";
+
+                    if let Err(err) = self.load_rankings(robotevents, skills_cache).await {
+                        return Some(
+                            CreateInteractionResponseFollowup::new()
+                                .content(format!("Failed to get skills ranking data from RobotEvents.\n```rs\n{err:?}```"))
+                                .ephemeral(true),
+                        )
+                    }
+                },
+                _ => {
+                    return Some(
+                        CreateInteractionResponseFollowup::new()
+                            .content("Unhandled component interaction. This shouldn't happen.")
+                            .ephemeral(true),
+                    )
+                },
+            }
+
+            let message_edit = command_interaction
+                .edit_response(
+                    &ctx,
+                    EditInteractionResponse::new()
+                        .embed(self.embed())
+                        .components(self.components()),
+                )
+                .await;
+
+            if message_edit.is_ok() {
+                None
+            } else {
+                Some(CreateInteractionResponseFollowup::new().content("Failed to edit embed.").ephemeral(true))
+            }
+        } else {
+            Some(
+                CreateInteractionResponseFollowup::new()
+                    .content("Unhandled component interaction. This shouldn't happen.")
+                    .ephemeral(true),
+            )
+        }
+    }
+}
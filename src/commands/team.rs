@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use robotevents::query::PaginatedQuery;
 use serenity::all::{
@@ -7,8 +8,8 @@ use serenity::all::{
     ComponentInteractionDataKind, ReactionType,
 };
 use serenity::builder::{
-    CreateActionRow, CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponse,
-    CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind,
+    CreateActionRow, CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed,
+    CreateInteractionResponseFollowup, CreateSelectMenu, CreateSelectMenuKind,
     CreateSelectMenuOption, EditInteractionResponse, CreateEmbedFooter,
 };
 use serenity::client::Context;
@@ -16,10 +17,15 @@ use serenity::model::{application::CommandInteraction, Color};
 
 use robotevents::{
     RobotEvents,
-    query::{TeamsQuery, SeasonsQuery, TeamAwardsQuery, TeamEventsQuery},
-    schema::{PaginatedResponse, Team, Event, Season, Award, IdInfo}
+    query::{TeamsQuery, SeasonsQuery, TeamAwardsQuery, TeamEventsQuery, TeamMatchesQuery, TeamRankingsQuery},
+    schema::{PaginatedResponse, Team, Event, Season, Award, IdInfo, Match, MatchRound, Alliance, Ranking}
 };
-use crate::api::skills::{SkillsCache, SkillsRanking};
+use crate::analytics::{self, MatchStatistics, SeasonRatingsCache, TrueSkillEngine};
+use crate::api::skills::{SkillsCache, TeamSkillsRanking};
+use crate::commands::{robotevents_event_url, season_type_buttons, IamCommand};
+use crate::guild_config::GuildConfig;
+use crate::lookup_index::LookupIndex;
+use crate::storage::Storage;
 use crate::api::vrc_data_analysis::{
     VRCDataAnalysis,
     schema::TeamInfo
@@ -27,12 +33,21 @@ use crate::api::vrc_data_analysis::{
 
 const MAX_PER_PAGE: i32 = 250;
 
+/// Discord won't show more than 25 autocomplete choices.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+/// Discord's limits on the total length of an embed, the length of a field's value, and the number of fields.
+const MAX_EMBED_LENGTH: usize = 6000;
+const MAX_FIELD_LENGTH: usize = 1024;
+const MAX_EMBED_FIELDS: usize = 25;
+
 /// Represents a possible embed sent by the `/team`` command.
 /// 
 /// - The Overview embed displays general information about a team.
 /// - The Awards embed displays information about a team's RobotEvents awards.
 /// - The Stats page displays team statistics and rankings.
 /// - The Events page displays a list of events that a team attended.
+/// - The Matches page displays a team's match results, grouped by event.
 ///  
 /// > Different embed "pages" may require different, separately-fetched pieces of data.
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
@@ -42,6 +57,7 @@ pub enum EmbedPage {
     Awards,
     Stats,
     Events,
+    Matches,
 }
 
 #[derive(Default, Clone, Debug, PartialEq)]
@@ -56,6 +72,7 @@ impl FromStr for EmbedPage {
             "stats" => Ok(Self::Stats),
             "awards" => Ok(Self::Awards),
             "events" => Ok(Self::Events),
+            "matches" => Ok(Self::Matches),
             _ => Err(ParseEmbedPageError),
         }
     }
@@ -68,6 +85,7 @@ impl std::fmt::Display for EmbedPage {
             Self::Stats => "stats",
             Self::Awards => "awards",
             Self::Events => "events",
+            Self::Matches => "matches",
         })
      }
 }
@@ -75,6 +93,64 @@ impl std::fmt::Display for EmbedPage {
 #[derive(Debug, Clone)]
 pub struct TeamCommandRequestError;
 
+/// Splits a match's alliances into the alliance that a team played on and the opposing alliance.
+///
+/// The opposing alliance is `None` for single-alliance matches, such as VIQC Teamwork matches.
+/// Returns `None` if the team didn't play in the match.
+fn team_alliances(robotevents_match: &Match, team_id: i32) -> Option<(&Alliance, Option<&Alliance>)> {
+    let own_idx = robotevents_match.alliances
+        .iter()
+        .position(|alliance| alliance.teams.iter().any(|t| t.team.id == team_id))?;
+    let opposing = robotevents_match.alliances
+        .iter()
+        .enumerate()
+        .find(|(idx, _)| *idx != own_idx)
+        .map(|(_, alliance)| alliance);
+
+    Some((&robotevents_match.alliances[own_idx], opposing))
+}
+
+/// Summarizes how far a team made it through an event's elimination bracket, given the matches it
+/// played at that event.
+///
+/// Returns `None` if the team didn't play in any scored elimination matches.
+fn elimination_result(event_matches: &[&Match], team_id: i32) -> Option<String> {
+    // Depth of each elimination round, from earliest to latest.
+    let round_depth = |round: i32| match analytics::match_round(round) {
+        Some(MatchRound::RoundOf16) => Some(1),
+        Some(MatchRound::Quarterfinals) => Some(2),
+        Some(MatchRound::Semifinals) => Some(3),
+        Some(MatchRound::Finals) => Some(4),
+        None if round == analytics::TOP_N_ROUND => Some(4),
+        _ => None,
+    };
+
+    let (depth, last_match) = event_matches
+        .iter()
+        .filter(|m| m.scored)
+        .filter_map(|m| Some((round_depth(m.round)?, *m)))
+        .max_by_key(|(depth, m)| (*depth, m.instance, m.matchnum))?;
+
+    // VIQC Teamwork finals don't have an opposing alliance, so there's no winner to report.
+    if last_match.round == analytics::TOP_N_ROUND {
+        let alliance = team_alliances(last_match, team_id)
+            .map(|(alliance, _)| alliance)
+            .or_else(|| last_match.alliances.first())?;
+        return Some(format!("Finals (Score: {})", alliance.score));
+    }
+
+    let (alliance, opposing_alliance) = team_alliances(last_match, team_id)?;
+    let won_last_match = alliance.score > opposing_alliance?.score;
+
+    Some(match (depth, won_last_match) {
+        (4, true) => "Tournament Champions".to_string(),
+        (4, false) => "Finalist".to_string(),
+        (3, _) => "Semifinals".to_string(),
+        (2, _) => "Quarterfinals".to_string(),
+        _ => "Round of 16".to_string(),
+    })
+}
+
 /// Handler for the "/team" command.
 /// The team command serves the purpose of displaying information and statistics about a singular
 /// RobotEvents team.
@@ -113,15 +189,47 @@ pub struct TeamCommand {
     /// List of events the team has attended.
     events: Option<PaginatedResponse<Event>>,
 
-    skills_ranking: Option<Option<SkillsRanking>>,
+    /// List of matches the team has played in.
+    matches: Option<PaginatedResponse<Match>>,
+
+    /// List of the team's qualification rankings at each event.
+    rankings: Option<PaginatedResponse<Ranking>>,
+
+    /// Every match from every event the team attended in the selected season, used for computing
+    /// statistics locally when vrc-data-analysis is unavailable.
+    season_matches: Option<Arc<Vec<Match>>>,
+
+    /// OPR, DPR and CCWM computed locally from [`Self::season_matches`], keyed by team ID and season ID.
+    /// This is `None` if the team has no scored matches to compute statistics from.
+    local_statistics: HashMap<(i32, i32), Option<MatchStatistics>>,
+
+    /// TrueSkill ratings computed locally by replaying [`Self::season_matches`].
+    local_ratings: Option<TrueSkillEngine>,
+
+    skills_ranking: Option<Option<TeamSkillsRanking>>,
+
+    /// Whether the stats page shows post-season skills rankings rather than the regular season.
+    skills_post_season: bool,
 }
 
 impl TeamCommand {
+    /// Creates an instance of [`Self`] for looking up a team outside of a `/team` interaction, such as
+    /// from another command.
+    pub fn new(team_number: impl Into<String>, program_id_filter: Option<i32>) -> Self {
+        Self {
+            team_number: Some(team_number.into()),
+            program_id_filter,
+            ..Default::default()
+        }
+    }
+
     /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
     /// 
     /// Contains metadata for the slash command that users will interact with through autocomplete.
     pub fn command(program_list: Option<PaginatedResponse<IdInfo>>) -> CreateCommand {
-        let team_opt = CreateCommandOption::new(CommandOptionType::String, "number", "Team Number").required(true);
+        let team_opt = CreateCommandOption::new(CommandOptionType::String, "number", "Team Number (defaults to your team from /iam)")
+            .required(false)
+            .set_autocomplete(true);
         let mut program_opt = CreateCommandOption::new(CommandOptionType::Integer, "program", "Program Name").required(false);
         if let Some(program_list) = program_list {
             for program in program_list.data.iter() {
@@ -144,9 +252,51 @@ impl TeamCommand {
                 CreateCommandOption::new(CommandOptionType::SubCommand, "events", "Event attendance from a team")
                     .add_sub_option(team_opt.clone())
                     .add_sub_option(program_opt.clone()),
+                CreateCommandOption::new(CommandOptionType::SubCommand, "matches", "Match results from a team")
+                    .add_sub_option(team_opt.clone())
+                    .add_sub_option(program_opt.clone()),
             ])
     }
 
+    /// Suggests teams from the [`LookupIndex`] for whichever team number option is being typed in.
+    ///
+    /// The user's team from `/iam` is suggested first if it matches. This is shared with other commands
+    /// that take team numbers, like `/predict`.
+    pub async fn team_suggestions(
+        interaction: &CommandInteraction,
+        lookup_index: &LookupIndex,
+        storage: Option<&Storage>,
+    ) -> CreateAutocompleteResponse {
+        let query = interaction.data.autocomplete().map(|opt| opt.value.trim().to_uppercase()).unwrap_or_default();
+        let mut response = CreateAutocompleteResponse::new();
+        let mut choice_count = 0;
+
+        let linked_number = IamCommand::linked_team(storage, interaction.user.id.get())
+            .await
+            .map(|link| link.team_number)
+            .filter(|number| number.to_uppercase().starts_with(&query));
+        if let Some(number) = &linked_number {
+            response = response.add_string_choice(format!("{number} (Your Team)"), number);
+            choice_count += 1;
+        }
+
+        for team in lookup_index.search_teams(&query, MAX_AUTOCOMPLETE_CHOICES).await {
+            if choice_count >= MAX_AUTOCOMPLETE_CHOICES {
+                break;
+            }
+            if linked_number.as_ref().is_some_and(|number| number.eq_ignore_ascii_case(&team.number)) {
+                continue;
+            }
+
+            // Choice names are limited to 100 characters.
+            let name = format!("{} | {} ({})", team.number, team.name, team.program).chars().take(100).collect::<String>();
+            response = response.add_string_choice(name, team.number);
+            choice_count += 1;
+        }
+
+        response
+    }
+
     /// Generate the message components associated with this command, including the page and season
     /// select menus.
     /// 
@@ -181,6 +331,10 @@ impl TeamCommand {
                         .emoji(ReactionType::Unicode("🗓️".to_string()))
                         .description("Event attendance from this team")
                         .default_selection(page_selection_id == "option_team_events"),
+                    CreateSelectMenuOption::new("Matches", "option_team_matches")
+                        .emoji(ReactionType::Unicode("⚔️".to_string()))
+                        .description("Match results throughout the season")
+                        .default_selection(page_selection_id == "option_team_matches"),
                 ],
             },
         ))];
@@ -189,7 +343,7 @@ impl TeamCommand {
         if let Some(active_seasons) = &self.active_seasons {
             // Also: Season selection does nothing on the overview page, since RobotEvents only returns the
             // latest info about a team, so there's no point in showing it there either.
-            let is_overview_page = if let EmbedPage::Overview = page_selection { true } else { false };
+            let is_overview_page = matches!(page_selection, EmbedPage::Overview);
 
             if !active_seasons.is_empty() && !is_overview_page {
                 components.push(CreateActionRow::SelectMenu(CreateSelectMenu::new(
@@ -211,6 +365,10 @@ impl TeamCommand {
             }
         }
 
+        if let EmbedPage::Stats = page_selection {
+            components.push(season_type_buttons("team_skills", self.skills_post_season));
+        }
+
         components
     }
 
@@ -219,8 +377,15 @@ impl TeamCommand {
     /// [`Self::components`].
     /// 
     /// Returned as an instance of [`serenity::builder::CreateEmbed`].
-    pub async fn embed(&mut self, page: EmbedPage, robotevents: &RobotEvents, vrc_data_analysis: &VRCDataAnalysis, skills_cache: &SkillsCache) -> CreateEmbed {
-        let team = match self.find_robotevents_team(&robotevents).await {
+    pub async fn embed(
+        &mut self,
+        page: EmbedPage,
+        robotevents: &RobotEvents,
+        vrc_data_analysis: &VRCDataAnalysis,
+        skills_cache: &SkillsCache,
+        season_ratings: &SeasonRatingsCache,
+    ) -> CreateEmbed {
+        let team = match self.find_robotevents_team(robotevents).await {
             Ok(team) => team,
             Err(err) => {
                 return CreateEmbed::new()
@@ -280,7 +445,7 @@ impl TeamCommand {
                     if let Some(team) = &self.team {
                         // This mess is necessary because reqwest::Error doesn't derive #[derive(Clone)]
                         // https://github.com/seanmonstar/reqwest/issues/1053
-                        match skills_cache.get_team_ranking(team, self.current_season.unwrap(), robotevents).await {
+                        match skills_cache.get_team_ranking(team, self.current_season.unwrap(), self.skills_post_season, robotevents).await {
                             Ok(ranking) => {
                                 self.skills_ranking = Some(ranking.clone());
                                 Ok(ranking)
@@ -322,12 +487,16 @@ impl TeamCommand {
 
                 match skills_ranking {
                     Ok(ranking) => {
-                        if let Some(ranking) = ranking {
+                        if let Some(TeamSkillsRanking { ranking, region_rank, country_rank }) = ranking {
                             embed = embed.field(
-                                "Skills",
+                                if self.skills_post_season { "Post-Season Skills" } else { "Skills" },
                                 format!(
-                                    "World Ranking: **#{}**\nDriver: **{}**\nProgramming: **{}**\nCombined: **{}**",
+                                    "World Ranking: **#{}**\n{} Ranking: **#{}**\n{} Ranking: **#{}**\nDriver: **{}**\nProgramming: **{}**\nCombined: **{}**",
                                     ranking.rank,
+                                    ranking.team.event_region,
+                                    region_rank,
+                                    ranking.team.country,
+                                    country_rank,
                                     ranking.scores.max_driver,
                                     ranking.scores.max_programming,
                                     ranking.scores.score
@@ -335,7 +504,7 @@ impl TeamCommand {
                                 false
                             );
                         } else {
-                            embed = embed.field("Skills", "No skills runs found for this team.", false);
+                            embed = embed.field(if self.skills_post_season { "Post-Season Skills" } else { "Skills" }, "No skills runs found for this team.", false);
                         }
                     },
                     Err(err) => {
@@ -343,6 +512,9 @@ impl TeamCommand {
                     },
                 }
 
+                let has_remote_statistics = matches!(data_analysis, Some(Ok(_)));
+                let supports_remote_statistics = data_analysis.is_some();
+
                 if let Some(analysis_result) = data_analysis {
                     match analysis_result {
                         Ok(analysis) => {
@@ -377,8 +549,94 @@ impl TeamCommand {
                             embed = embed.field("Failed to get team statistics from vrc-data-analysis.", format!("```rs\n{err:?}```"), false);
                         }
                     }
-                } else {
-                    embed = embed.description("*Note: TrueSkill and match analysis data is only available for VRC teams at the moment.*");
+                }
+
+                // Fall back to computing TrueSkill and match statistics ourselves when vrc-data-analysis
+                // can't provide them.
+                if !has_remote_statistics {
+                    let mut has_local_statistics = false;
+
+                    // Season-wide ratings are built in the background, so until they're ready the team is only
+                    // rated against the teams at its own events.
+                    let season_wide_ratings = match self.current_season {
+                        Some(season_id) => season_ratings.get(season_id).await,
+                        None => None,
+                    };
+
+                    if let Some(ratings) = &season_wide_ratings {
+                        let rating = ratings.rating(&team.number);
+
+                        if rating.matches_played > 0 {
+                            has_local_statistics = true;
+
+                            embed = embed.field(
+                                "TrueSkill",
+                                format!(
+                                    "TrueSkill: **{:.2}** (μ {:.2}, σ {:.2})\nWorld Ranking: **#{}** of {}",
+                                    rating.skill(),
+                                    rating.mu,
+                                    rating.sigma,
+                                    ratings.ranking(&team.number).unwrap_or_default(),
+                                    ratings.rankings().len()
+                                ),
+                                false
+                            );
+                        }
+                    } else if let Some(ratings) = self.local_ratings(robotevents).await {
+                        let rating = ratings.rating(&team.number);
+
+                        if rating.matches_played > 0 {
+                            has_local_statistics = true;
+
+                            // Only teams that played at this team's events are rated, so this isn't a world ranking.
+                            embed = embed.field(
+                                "TrueSkill",
+                                format!(
+                                    "TrueSkill: **{:.2}** (μ {:.2}, σ {:.2})\nRanking among opponents' events: **#{}** of {}",
+                                    rating.skill(),
+                                    rating.mu,
+                                    rating.sigma,
+                                    ratings.ranking(&team.number).unwrap_or_default(),
+                                    ratings.rankings().len()
+                                ),
+                                false
+                            );
+                        }
+                    }
+
+                    if let Some(statistics) = self.local_match_statistics(robotevents).await {
+                        has_local_statistics = true;
+
+                        let format_optional = |value: Option<f64>| {
+                            value.map(|value| format!("{value:.2}")).unwrap_or("N/A".to_string())
+                        };
+
+                        embed = embed
+                            .field(
+                                "Match Statistics",
+                                format!(
+                                    "OPR: **{:.2}**\nDPR: **{}**\nCCWM: **{}**\nMatches Played: **{}**",
+                                    statistics.opr,
+                                    format_optional(statistics.dpr),
+                                    format_optional(statistics.ccwm),
+                                    statistics.matches_played
+                                ),
+                                false
+                            )
+                            .footer(CreateEmbedFooter::new(if season_wide_ratings.is_some() {
+                                "TrueSkill computed from RobotEvents match results across the season. Match Data computed from this team's events"
+                            } else {
+                                "TrueSkill & Match Data computed from RobotEvents match results at this team's events"
+                            }));
+                    }
+
+                    if !supports_remote_statistics {
+                        embed = embed.description(if has_local_statistics {
+                            "*vrc-data-analysis only covers VRC teams, so these statistics were computed from RobotEvents match results instead.*"
+                        } else {
+                            "*Note: TrueSkill data is only available for VRC teams at the moment.*"
+                        });
+                    }
                 }
             },
             EmbedPage::Awards => {
@@ -463,6 +721,17 @@ impl TeamCommand {
                     }
                 };
 
+                // Rankings and matches are only used to add placement details to each event, so a failure
+                // to fetch them isn't fatal to the whole page.
+                if let Some(team) = &self.team {
+                    if self.rankings.is_none() {
+                        self.rankings = team.rankings(robotevents, TeamRankingsQuery::new().season(self.current_season.unwrap()).per_page(MAX_PER_PAGE)).await.ok();
+                    }
+                    if self.matches.is_none() {
+                        self.matches = team.matches(robotevents, TeamMatchesQuery::new().season(self.current_season.unwrap()).per_page(MAX_PER_PAGE)).await.ok();
+                    }
+                }
+
                 embed = embed
                     .title(format!(
                         "{} ({}, {}) Events",
@@ -484,29 +753,151 @@ impl TeamCommand {
                 }
 
                 for event in events.data {
-                    embed = embed.field(
-                        event.name,
-                        if let Some(code) = event.program.code {
+                    let mut placement = Vec::new();
+
+                    if let Some(ranking) = self.rankings
+                        .as_ref()
+                        .and_then(|rankings| rankings.data.iter().find(|r| r.event.id == event.id))
+                    {
+                        placement.push(format!("Rank: **#{}**", ranking.rank));
+                        placement.push(format!("Record: **{}-{}-{}**", ranking.wins, ranking.losses, ranking.ties));
+                    }
+
+                    if let Some(matches) = &self.matches {
+                        let event_matches = matches.data
+                            .iter()
+                            .filter(|m| m.event.id == event.id)
+                            .collect::<Vec<_>>();
+
+                        if let Some(result) = elimination_result(&event_matches, team.id) {
+                            placement.push(format!("Eliminations: **{result}**"));
+                        }
+                    }
+
+                    placement.push(if event.program.code.is_some() {
+                        format!("[View More]({})", robotevents_event_url(&event.program, &event.sku))
+                    } else {
+                        event.sku
+                    });
+
+                    embed = embed.field(&event.name, placement.join("\n"), true);
+                }
+            },
+            EmbedPage::Matches => {
+                let matches = if let Some(matches) = &self.matches {
+                    matches.clone()
+                } else {
+                    if let Some(team) = &self.team {
+                        match team.matches(robotevents, TeamMatchesQuery::new().season(self.current_season.unwrap()).per_page(MAX_PER_PAGE)).await {
+                            Ok(matches) => {
+                                self.matches = Some(matches.clone());
+                                matches
+                            },
+                            Err(err) => {
+                                return CreateEmbed::new()
+                                    .title("Failed to fetch RobotEvents match data.")
+                                    .description(format!("```rs\n{err:?}```"));
+                            },
+                        }
+                    } else {
+                        return CreateEmbed::new()
+                            .title("Invalid team data.");
+                    }
+                };
+
+                let title = format!(
+                    "{} ({}, {}) Matches",
+                    team.number, program_code, team.grade
+                );
+
+                // Group matches by event, keeping events in the order RobotEvents returned them in.
+                let mut categorized_matches: Vec<(String, Vec<String>)> = Vec::new();
+                for robotevents_match in matches.data.iter() {
+                    let Some((alliance, opposing_alliance)) = team_alliances(robotevents_match, team.id) else {
+                        continue;
+                    };
+
+                    let partners = alliance.teams
+                        .iter()
+                        .filter(|t| t.team.id != team.id)
+                        .map(|t| t.team.name.clone())
+                        .collect::<Vec<_>>();
+                    let partners = if partners.is_empty() { "Solo".to_string() } else { format!("w/ {}", partners.join(", ")) };
+
+                    let line = if let Some(opposing_alliance) = opposing_alliance {
+                        let opponents = opposing_alliance.teams
+                            .iter()
+                            .map(|t| t.team.name.clone())
+                            .collect::<Vec<_>>();
+
+                        let result = if !robotevents_match.scored {
+                            "Unplayed".to_string()
+                        } else {
                             format!(
-                                "[View More](https://robotevents.com/robot-competitions/{}/{}.html)",
-                                match event.program.id {
-                                    1 => "vex-robotics-competition",
-                                    4 => "college-competition",
-                                    41 => "vex-iq-competition",
-                                    57 => "vex-ai-competition",
-                                    46 => "tsavrc",
-                                    47 => "tsaviqc",
-                                    56 => "vex-factory-automation-competition",
-                                    _ => code.as_str(),
-                                },
-                                event.sku
+                                "**{}-{}** ({})",
+                                alliance.score,
+                                opposing_alliance.score,
+                                match alliance.score.cmp(&opposing_alliance.score) {
+                                    std::cmp::Ordering::Greater => "W",
+                                    std::cmp::Ordering::Less => "L",
+                                    std::cmp::Ordering::Equal => "T",
+                                }
                             )
+                        };
+
+                        format!("{}: {} vs {} | {}", robotevents_match.name, partners, opponents.join(", "), result)
+                    } else {
+                        // Single-alliance (e.g. VIQC Teamwork) matches only have a shared score.
+                        let result = if !robotevents_match.scored {
+                            "Unplayed".to_string()
                         } else {
-                            event.sku
-                        },
-                        true,
-                    );
+                            format!("Score: **{}**", alliance.score)
+                        };
+
+                        format!("{}: {} | {}", robotevents_match.name, partners, result)
+                    };
+
+                    match categorized_matches.iter_mut().find(|(name, _)| name == &robotevents_match.event.name) {
+                        Some((_, lines)) => lines.push(line),
+                        None => categorized_matches.push((robotevents_match.event.name.clone(), vec![line])),
+                    }
+                }
+
+                if categorized_matches.is_empty() {
+                    embed = embed.description("No matches found.");
                 }
+
+                // Embeds are limited to 6000 characters in total and 1024 characters per field, so
+                // matches are cut off once either budget runs out. Some room is kept for the title and
+                // the note about omitted events.
+                let mut remaining_length = MAX_EMBED_LENGTH - title.len() - 64;
+                let mut omitted_events = 0;
+                for (event_idx, (event_name, lines)) in categorized_matches.iter().enumerate() {
+                    let field_length = (MAX_FIELD_LENGTH + event_name.len()).min(remaining_length);
+                    if event_idx >= MAX_EMBED_FIELDS || field_length < event_name.len() + 64 {
+                        omitted_events = categorized_matches.len() - event_idx;
+                        break;
+                    }
+
+                    let mut value = String::new();
+                    for (idx, line) in lines.iter().enumerate() {
+                        if event_name.len() + value.len() + line.len() + 32 > field_length {
+                            value.push_str(&format!("*...and {} more.*", lines.len() - idx));
+                            break;
+                        }
+                        value.push_str(line);
+                        value.push('\n');
+                    }
+
+                    remaining_length -= event_name.len() + value.len();
+                    embed = embed.field(event_name, value, false);
+                }
+
+                if omitted_events > 0 {
+                    embed = embed.description(format!("*...and {} more events.*", omitted_events));
+                }
+
+                embed = embed.title(title);
             },
         }
 
@@ -516,7 +907,8 @@ impl TeamCommand {
     /// Generate an initial response message to a command interaction.
     /// 
     /// When the bot recieves a [`serenity::model::application::CommandInteraction`] as a result of a user
-    /// sending a slash command, this message will be constructed and sent in response.
+    /// sending a slash command, the response is deferred and then edited to this message, since building
+    /// the stats page can take a while.
     /// 
     /// The `/team` command has two primary user-provided arguments:
     /// - A team number, which is eventually searched for on RobotEvents.
@@ -524,6 +916,7 @@ impl TeamCommand {
     ///   team in (e.g. VRC, VIQC, VAIC...).
     /// 
     /// > By default, this response will start on the team overview [`EmbedPage`].
+    #[allow(clippy::too_many_arguments)]
     pub async fn response(
         &mut self,
         _ctx: &Context,
@@ -531,48 +924,71 @@ impl TeamCommand {
         robotevents: &RobotEvents,
         vrc_data_analysis: &VRCDataAnalysis,
         skills_cache: &SkillsCache,
-    ) -> CreateInteractionResponseMessage {
+        season_ratings: &SeasonRatingsCache,
+        storage: Option<&Storage>,
+        config: &GuildConfig,
+    ) -> EditInteractionResponse {
         let options = if let CommandDataOptionValue::SubCommand(cmd) = &interaction.data.options[0].value {
             cmd
         }  else {
-            return CreateInteractionResponseMessage::new().content("Invalid subcommand option.");
+            return EditInteractionResponse::new().content("Invalid subcommand option.");
         };
 
         self.current_page = if let Ok(parsed_page) = interaction.data.options[0].name.parse::<EmbedPage>() {
             parsed_page
         } else {
-            return CreateInteractionResponseMessage::new().content("Failed to parse subcommand type.");
+            return EditInteractionResponse::new().content("Failed to parse subcommand type.");
         };
 
-        // Set the initially requested team number from command arguments.
-        self.team_number =
-            if let CommandDataOptionValue::String(number) = &options[0].value {
-                Some(number.to_string())
-            } else {
-                return CreateInteractionResponseMessage::new().content("Invalid team number.");
-            };
+        let option = |name: &str| options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
 
-        // Set program filter if used.
-        self.program_id_filter = if options.len() < 2 {
-            None
-        } else if let CommandDataOptionValue::Integer(id) = &options[1].value {
-            i32::try_from(*id).ok() // This conversion from i64 to i32 shouldn't ever realistically fail...
-        } else {
-            return CreateInteractionResponseMessage::new()
-                .content("Invalid RobotEvents program value.");
+        // Set program filter if used, otherwise falling back to the server's default program.
+        self.program_id_filter = match option("program") {
+            Some(CommandDataOptionValue::Integer(id)) => i32::try_from(*id).ok(), // This conversion from i64 to i32 shouldn't ever realistically fail...
+            Some(_) => {
+                return EditInteractionResponse::new()
+                    .content("Invalid RobotEvents program value.");
+            },
+            None => config.default_program,
+        };
+
+        // Set the initially requested team number from command arguments, falling back to the team that the
+        // user linked with `/iam`.
+        self.team_number = match option("number") {
+            Some(CommandDataOptionValue::String(number)) => Some(number.to_string()),
+            Some(_) => return EditInteractionResponse::new().content("Invalid team number."),
+            None => match IamCommand::linked_team(storage, interaction.user.id.get()).await {
+                Some(link) => {
+                    // A linked team is always looked up in its own program.
+                    if option("program").is_none() {
+                        self.program_id_filter = Some(link.program_id);
+                    }
+                    Some(link.team_number)
+                },
+                None => {
+                    return EditInteractionResponse::new()
+                        .content("Provide a team number, or link your team with `/iam` to leave it out.");
+                },
+            },
         };
 
         // Fetch RobotEvents team data over HTTP.
-        if let Ok(team) = self.find_robotevents_team(&robotevents).await {
+        if let Ok(team) = self.find_robotevents_team(robotevents).await {
 
             // Find a list of seasons that the fetched team was active in using a separate endpoint.
             self.active_seasons = match robotevents.seasons(SeasonsQuery::new().team(team.id).per_page(MAX_PER_PAGE)).await {
                 Ok(seasons) => {
-                    self.current_season = Some(seasons.data[0].id);
+                    // Start on the server's default season if the team was active in it, otherwise the latest.
+                    let default_season = config.default_season.as_ref().and_then(|years| {
+                        seasons.data
+                            .iter()
+                            .find(|season| format!("{}-{}", season.years_start, season.years_end) == *years)
+                    });
+                    self.current_season = Some(default_season.unwrap_or(&seasons.data[0]).id);
                     Some(seasons.data)
                 },
                 Err(err) => {
-                    return CreateInteractionResponseMessage::new()
+                    return EditInteractionResponse::new()
                         .add_embed(
                             CreateEmbed::new().title("Failed to get season information about team from RobotEvents.")
                             .description(format!("```rs\n{err:?}```")),
@@ -582,12 +998,77 @@ impl TeamCommand {
 
             // This check is technically redundant, since we just set `self.current_page` to Some(T), but
             // it keeps us from have to unnecessarily clone using other methods, so... ¯\_(ツ)_/¯
-            CreateInteractionResponseMessage::new()
-                .embed(self.embed(self.current_page, robotevents, vrc_data_analysis, skills_cache).await)
+            EditInteractionResponse::new()
+                .embed(self.embed(self.current_page, robotevents, vrc_data_analysis, skills_cache, season_ratings).await)
                 .components(self.components(self.current_page, self.current_season.unwrap()))
         } else {
-            CreateInteractionResponseMessage::new().content("Failed to find a RobotEvents team with this number.")
+            EditInteractionResponse::new().content("Failed to find a RobotEvents team with this number.")
+        }
+    }
+
+    /// Returns every match from every event the team attended in the currently selected season.
+    ///
+    /// The result is cached on [`Self`] until the selected season changes.
+    async fn season_matches(&mut self, robotevents: &RobotEvents) -> Option<Arc<Vec<Match>>> {
+        if self.season_matches.is_none() {
+            let team = self.team.clone()?;
+
+            if self.events.is_none() {
+                self.events = team.events(robotevents, TeamEventsQuery::new().season(self.current_season?).per_page(MAX_PER_PAGE)).await.ok();
+            }
+
+            let mut matches = Vec::new();
+            for event in self.events.as_ref()?.data.iter() {
+                matches.extend(analytics::event_matches(robotevents, event).await.ok()?);
+            }
+
+            self.season_matches = Some(Arc::new(matches));
+        }
+
+        self.season_matches.clone()
+    }
+
+    /// Returns OPR, DPR and CCWM for this team over the currently selected season, computed from the
+    /// matches of every event the team attended.
+    ///
+    /// The result is cached on [`Self`], since the system being solved grows with every team at every event.
+    async fn local_match_statistics(&mut self, robotevents: &RobotEvents) -> Option<MatchStatistics> {
+        let team_id = self.team.as_ref()?.id;
+        let key = (team_id, self.current_season?);
+
+        if let Some(statistics) = self.local_statistics.get(&key) {
+            return *statistics;
+        }
+
+        let matches = self.season_matches(robotevents).await?;
+
+        // Solving for every team at every event is CPU-bound, so it's kept off the async runtime.
+        let statistics = tokio::task::spawn_blocking(move || analytics::match_statistics(matches.iter()).remove(&team_id))
+            .await
+            .ok()?;
+        self.local_statistics.insert(key, statistics);
+
+        statistics
+    }
+
+    /// Returns a TrueSkill rating engine built by replaying the matches of every event the team
+    /// attended in the currently selected season.
+    ///
+    /// The result is cached on [`Self`] until the selected season changes.
+    async fn local_ratings(&mut self, robotevents: &RobotEvents) -> Option<&TrueSkillEngine> {
+        if self.local_ratings.is_none() {
+            let matches = self.season_matches(robotevents).await?;
+
+            self.local_ratings = tokio::task::spawn_blocking(move || {
+                let mut engine = TrueSkillEngine::default();
+                engine.replay(matches.iter());
+                engine
+            })
+            .await
+            .ok();
         }
+
+        self.local_ratings.as_ref()
     }
 
     /// Returns the RobotEvents team data associated with this instance of [`Self`].
@@ -624,7 +1105,7 @@ impl TeamCommand {
             // From there, pick the team with the lowest numeric program ID (VRC has the lowest ID being `1`).
             teams.data.sort_by_key(|team| (!team.registered, team.program.id));
 
-            if let Some(team) = teams.data.iter().next() {
+            if let Some(team) = teams.data.first() {
                 self.team = Some(team.clone()); // Cache value for later use. 
                 return Ok(team.clone());
             }
@@ -633,8 +1114,12 @@ impl TeamCommand {
         Err(TeamCommandRequestError)
     }
     
-    /// Event handler that outputs a response message based on a user interaction with a message component
-    /// (such as a select menu).
+    /// Event handler for user interactions with a message component (such as a select menu).
+    ///
+    /// The interaction is acknowledged straight away and the original message is edited once the new page
+    /// is ready, since building the stats page can take longer than Discord's 3 second deadline. Returns a
+    /// followup message to send if something went wrong.
+    #[allow(clippy::too_many_arguments)]
     pub async fn component_interaction_response(
         &mut self,
         ctx: &Context,
@@ -643,31 +1128,25 @@ impl TeamCommand {
         robotevents: &RobotEvents,
         vrc_data_analysis: &VRCDataAnalysis,
         skills_cache: &SkillsCache,
-    ) -> CreateInteractionResponse {
+        season_ratings: &SeasonRatingsCache,
+    ) -> Option<CreateInteractionResponseFollowup> {
+        if component_interaction.defer(&ctx).await.is_err() {
+            return None;
+        }
+
         if let ComponentInteractionDataKind::StringSelect { values } = &component_interaction.data.kind {
             let changed_value: &str = values.first().unwrap().as_ref();
 
-            let message_edit = if changed_value.starts_with("option_team_") { // User changed page
+            if changed_value.starts_with("option_team_") { // User changed page
                 self.current_page = changed_value.trim_start_matches("option_team_").parse::<EmbedPage>().unwrap();
-
-                command_interaction
-                    .edit_response(
-                        &ctx,
-                        EditInteractionResponse::new()
-                            .embed(self.embed(self.current_page, robotevents, vrc_data_analysis, skills_cache).await)
-                            .components(self.components(
-                                self.current_page,
-                                self.current_season.unwrap(),
-                            )),
-                    )
-                    .await
             } else if changed_value.starts_with("option_season_") { // User changed season
                 let season_id = if let Ok(parsed_id) = changed_value.trim_start_matches("option_season_").parse::<i32>() {
                     parsed_id
                 } else {
-                    return CreateInteractionResponse::Message(
-                        CreateInteractionResponseMessage::new()
-                            .content(format!("Failed to parse season ID for {}.", changed_value)),
+                    return Some(
+                        CreateInteractionResponseFollowup::new()
+                            .content(format!("Failed to parse season ID for {}.", changed_value))
+                            .ephemeral(true),
                     )
                 };
 
@@ -676,38 +1155,57 @@ impl TeamCommand {
                 // Reset season-specific information.
                 self.awards = None;
                 self.events = None;
+                self.matches = None;
+                self.rankings = None;
+                self.season_matches = None;
+                self.local_ratings = None;
                 self.skills_ranking = None;
-
-                command_interaction
-                    .edit_response(
-                        &ctx,
-                        EditInteractionResponse::new()
-                            .embed(self.embed(self.current_page, robotevents, vrc_data_analysis, skills_cache).await)
-                            .components(self.components(
-                                self.current_page,
-                                season_id,
-                            )),
-                    )
-                    .await
             } else {
-                return CreateInteractionResponse::Message(
-                    CreateInteractionResponseMessage::new()
-                        .content("Unhandled component interaction. This shouldn't happen."),
-                )
-            };
-
-            if message_edit.is_ok() {
-                CreateInteractionResponse::Acknowledge
-            } else {
-                CreateInteractionResponse::Message(
-                    CreateInteractionResponseMessage::new().content("Failed to edit embed."),
+                return Some(
+                    CreateInteractionResponseFollowup::new()
+                        .content("Unhandled component interaction. This shouldn't happen.")
+                        .ephemeral(true),
                 )
             }
+        } else if let ComponentInteractionDataKind::Button = &component_interaction.data.kind {
+            match component_interaction.data.custom_id.as_str() {
+                "team_skills_regular_season" => self.skills_post_season = false,
+                "team_skills_post_season" => self.skills_post_season = true,
+                _ => {
+                    return Some(
+                        CreateInteractionResponseFollowup::new()
+                            .content("Unhandled component interaction. This shouldn't happen.")
+                            .ephemeral(true),
+                    )
+                },
+            }
+
+            // Regular season and post-season rankings are separate leaderboards.
+            self.skills_ranking = None;
         } else {
-            return CreateInteractionResponse::Message(
-                CreateInteractionResponseMessage::new()
-                    .content("Unhandled component interaction. This shouldn't happen."),
+            return Some(
+                CreateInteractionResponseFollowup::new()
+                    .content("Unhandled component interaction. This shouldn't happen.")
+                    .ephemeral(true),
             )
         }
+
+        let message_edit = command_interaction
+            .edit_response(
+                &ctx,
+                EditInteractionResponse::new()
+                    .embed(self.embed(self.current_page, robotevents, vrc_data_analysis, skills_cache, season_ratings).await)
+                    .components(self.components(
+                        self.current_page,
+                        self.current_season.unwrap(),
+                    )),
+            )
+            .await;
+
+        if message_edit.is_ok() {
+            None
+        } else {
+            Some(CreateInteractionResponseFollowup::new().content("Failed to edit embed.").ephemeral(true))
+        }
     }
 }
@@ -0,0 +1,48 @@
+use serenity::all::{CommandDataOptionValue, CommandOptionType, Permissions};
+use serenity::builder::{CreateCommand, CreateCommandOption, CreateInteractionResponseMessage};
+use serenity::client::Context;
+use serenity::model::application::CommandInteraction;
+
+use crate::watchlist::Watchlist;
+
+/// Handler for the "/unfollow" command.
+/// Removes a team from the guild's watchlist.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct UnfollowCommand;
+
+impl UnfollowCommand {
+    /// Get a [`serenity::builder::CreateCommand`] instance associated with this command.
+    pub fn command() -> CreateCommand {
+        CreateCommand::new("unfollow")
+            .description("Stops posting updates about a team")
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "number", "Team Number")
+                    .required(true)
+            )
+    }
+
+    pub async fn response(
+        &self,
+        _ctx: &Context,
+        interaction: &CommandInteraction,
+        watchlist: &Watchlist,
+    ) -> CreateInteractionResponseMessage {
+        let Some(guild_id) = interaction.guild_id else {
+            return CreateInteractionResponseMessage::new().content("Teams can only be unfollowed from within a server.");
+        };
+
+        let team_number = match interaction.data.options.first().map(|opt| &opt.value) {
+            Some(CommandDataOptionValue::String(team_number)) => team_number.trim(),
+            _ => return CreateInteractionResponseMessage::new().content("Invalid team number."),
+        };
+
+        match watchlist.unfollow(guild_id.get(), team_number).await {
+            Some(watched) => CreateInteractionResponseMessage::new()
+                .content(format!("No longer following **{}**.", watched.team_number)),
+            None => CreateInteractionResponseMessage::new()
+                .content(format!("This server isn't following **{team_number}**.")),
+        }
+    }
+}
\ No newline at end of file
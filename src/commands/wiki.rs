@@ -1,62 +1,100 @@
-use std::collections::HashMap;
-use std::sync::LazyLock;
-
 use serenity::all::{
     CommandDataOptionValue, CommandOptionType
 };
 use serenity::builder::{
-    CreateCommand, CreateCommandOption, CreateEmbed,
+    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
     CreateInteractionResponseMessage
 };
 use serenity::client::Context;
 use serenity::model::application::CommandInteraction;
 
+use crate::knowledge::KnowledgeBase;
+
+/// Discord won't show more than 25 autocomplete choices.
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
 #[derive(Default, Clone, Debug, PartialEq)]
 pub struct WikiCommand;
 
-static PRIVILEGES: LazyLock<HashMap<&'static str, (&'static str, &'static str)>> = LazyLock::new(|| {
-    let mut map = HashMap::new();
+impl WikiCommand {
+    pub fn command(knowledge: &KnowledgeBase) -> CreateCommand {
+        let mut source_opt = CreateCommandOption::new(CommandOptionType::String, "source", "Where to search (defaults to everywhere)").required(false);
+        for source in knowledge.sources() {
+            source_opt = source_opt.add_string_choice(source.name(), source.id());
+        }
 
-    map.insert("main", ("https://wiki.purduesigbots.com/", "Sigbots: Main Page"));
-    map.insert("building", ("https://wiki.purduesigbots.com/hardware/misc.-vex-parts", "Sigbots: Useful Building Techniques"));
-    map.insert("structure", ("https://wiki.purduesigbots.com/hardware/misc.-vex-parts-1/structure", "Sigbots: Structural Parts"));
-    map.insert("motion", ("https://wiki.purduesigbots.com/hardware/misc.-vex-parts-1/motion", "Sigbots: Motion parts"));
-    map.insert("joints", ("https://wiki.purduesigbots.com/hardware/vex-joints", "Sigbots: Joints"));
-    map.insert("drives", ("https://wiki.purduesigbots.com/hardware/vex-drivetrains", "Sigbots: Drivetrains"));
-    map.insert("lifts", ("https://wiki.purduesigbots.com/hardware/lifts", "Sigbots: Lift Mechanisms"));
-    map.insert("intakes", ("https://wiki.purduesigbots.com/hardware/intakes", "Sigbots: Intake Mechanisms"));
-    map.insert("launchers", ("https://wiki.purduesigbots.com/hardware/shooting-mechanisms", "Sigbots: Launching Mechanisms"));
-    map.insert("pneumatics", ("https://wiki.purduesigbots.com/hardware/pneumatics", "Sigbots: Pneumatics"));
+        CreateCommand::new("wiki")
+            .description("Search the Purdue Sigbots Wiki and PROS docs")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "query", "What to search for (e.g. drivetrains or PID)")
+                    .required(true)
+                    .set_autocomplete(true)
+            )
+            .add_option(source_opt)
+    }
 
-    map
-});
+    /// Suggests articles matching the `query` option as the user types it, from the selected source if
+    /// there is one.
+    pub fn autocomplete(&self, interaction: &CommandInteraction, knowledge: &KnowledgeBase) -> CreateAutocompleteResponse {
+        let query = interaction.data.autocomplete().map(|opt| opt.value).unwrap_or_default();
+        let source_id = interaction.data.options
+            .iter()
+            .find(|opt| opt.name == "source")
+            .and_then(|opt| opt.value.as_str());
 
-impl WikiCommand {
-    pub fn command() -> CreateCommand {
-        let mut option = CreateCommandOption::new(CommandOptionType::String, "name", "The article name").required(true);
-        for (key, (url, title)) in PRIVILEGES.iter() {
-            option = option.add_string_choice(*title, *key).description(*url);
-        }
-        CreateCommand::new("wiki").description("Link an article from the Purdue Sigbots Wiki or VEX Knowledge Base").add_option(option)
+        // Articles are suggested by title, so picking one searches for it by name.
+        knowledge.search(query, source_id, MAX_AUTOCOMPLETE_CHOICES)
+            .into_iter()
+            .fold(CreateAutocompleteResponse::new(), |response, result| {
+                let name = format!("{} ({})", result.article.title, result.source.name()).chars().take(100).collect::<String>();
+                response.add_string_choice(name, result.article.title.chars().take(100).collect::<String>())
+            })
     }
 
     pub fn response(
         &self,
         _ctx: &Context,
-        interaction: &CommandInteraction
+        interaction: &CommandInteraction,
+        knowledge: &KnowledgeBase,
     ) -> CreateInteractionResponseMessage {
-        let name = if let CommandDataOptionValue::String(arg) = &interaction.data.options[0].value {
-            arg.trim()
-        } else {
-            return CreateInteractionResponseMessage::new().content("No argument provided");
+        let option = |name: &str| interaction.data.options.iter().find(|opt| opt.name == name).map(|opt| &opt.value);
+
+        let query = match option("query") {
+            Some(CommandDataOptionValue::String(query)) => query.trim(),
+            _ => return CreateInteractionResponseMessage::new().content("No argument provided"),
         };
-    
-        if PRIVILEGES.contains_key(name) {
-            CreateInteractionResponseMessage::new().add_embed(
-                CreateEmbed::new().title("Here you go").url(PRIVILEGES[name].0)
-            )
-        } else {
-            CreateInteractionResponseMessage::new().content("Couldn't find the article you were looking for")
+        let source_id = match option("source") {
+            Some(CommandDataOptionValue::String(source_id)) => Some(source_id.as_str()),
+            _ => None,
+        };
+
+        let matches = knowledge.search(query, source_id, 4);
+        let Some((best, others)) = matches.split_first() else {
+            return CreateInteractionResponseMessage::new().content("Couldn't find the article you were looking for");
+        };
+
+        let mut embed = CreateEmbed::new()
+            .title(&best.article.title)
+            .url(best.url())
+            .description(&best.article.summary)
+            .footer(CreateEmbedFooter::new(best.source.name()))
+            .color(best.source.color());
+
+        if !best.article.headings.is_empty() {
+            embed = embed.field("Sections", best.article.headings.join(", "), false);
         }
+        if !others.is_empty() {
+            embed = embed.field(
+                "Other Matches",
+                others
+                    .iter()
+                    .map(|other| format!("[{}]({}) ({})", other.article.title, other.url(), other.source.name()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                false,
+            );
+        }
+
+        CreateInteractionResponseMessage::new().add_embed(embed)
     }
 }
\ No newline at end of file
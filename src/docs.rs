@@ -0,0 +1,99 @@
+use serde::Deserialize;
+
+use crate::search::fuzzy_score;
+
+/// Index of LemLib's API that ships with the bot. Regenerated with `scripts/generate_docs_index.py` from
+/// LemLib's Doxygen XML and the Sphinx inventory of its published docs.
+const BUNDLED_LEMLIB_INDEX: &str = include_str!("../assets/docs/lemlib.json");
+
+/// A documented function or constructor parameter.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct DocParameter {
+    pub name: String,
+    pub description: String,
+}
+
+/// A class, struct, method or function from a project's API reference.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct DocSymbol {
+    /// Fully qualified name (e.g. "lemlib::Chassis::moveToPoint").
+    pub name: String,
+
+    /// Kind of symbol (e.g. "class" or "method").
+    pub kind: String,
+
+    pub signature: String,
+    pub description: String,
+
+    /// Path of the symbol's documentation, relative to the index's base URL. Includes the anchor of the
+    /// symbol's entry on the page.
+    pub path: String,
+
+    #[serde(default)]
+    pub parameters: Vec<DocParameter>,
+}
+
+impl DocSymbol {
+    /// The last part of the symbol's name (e.g. "moveToPoint").
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit("::").next().unwrap_or(&self.name)
+    }
+}
+
+/// Searchable index of a project's API reference.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct DocsIndex {
+    pub project: String,
+
+    /// Version of the project that the index was generated from.
+    pub version: String,
+
+    pub base_url: String,
+    pub symbols: Vec<DocSymbol>,
+}
+
+impl DocsIndex {
+    /// The LemLib index that is compiled into the bot.
+    pub fn lemlib() -> Self {
+        serde_json::from_str(BUNDLED_LEMLIB_INDEX).expect("Bundled LemLib docs index is invalid.")
+    }
+
+    /// Full URL of a symbol's documentation.
+    pub fn url(&self, symbol: &DocSymbol) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), symbol.path)
+    }
+
+    /// Finds a symbol by its fully qualified or short name, ignoring case.
+    pub fn symbol(&self, name: &str) -> Option<&DocSymbol> {
+        let name = name.trim();
+
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.name.eq_ignore_ascii_case(name))
+            .or_else(|| self.symbols.iter().find(|symbol| symbol.short_name().eq_ignore_ascii_case(name)))
+    }
+
+    /// Returns up to `limit` symbols matching a query, best matches first. An empty query returns symbols in
+    /// index order.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&DocSymbol> {
+        if query.trim().is_empty() {
+            return self.symbols.iter().take(limit).collect();
+        }
+
+        let mut matches = self.symbols
+            .iter()
+            .filter_map(|symbol| {
+                let score = fuzzy_score(query, &[
+                    (symbol.short_name(), 4.0),
+                    (&symbol.name, 2.0),
+                    (&symbol.description, 1.0),
+                ]);
+
+                (score > 0.0).then_some((score, symbol))
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.name.cmp(&b.1.name)));
+        matches.into_iter().take(limit).map(|(_, symbol)| symbol).collect()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,87 @@
+use crate::storage::{self, Storage, StorageError};
+
+const DEFAULT_PROGRAM_KEY: &str = "default_program";
+const DEFAULT_SEASON_KEY: &str = "default_season";
+const EPHEMERAL_KEY: &str = "ephemeral";
+const ALLOWED_CHANNELS_KEY: &str = "allowed_channels";
+
+/// Settings that guild admins can change with `/config`.
+///
+/// Outside of guilds (or without storage), every command uses the default settings.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct GuildConfig {
+    /// Program that team lookups are filtered to when a command's `program` option is left out.
+    pub default_program: Option<i32>,
+
+    /// Season years (e.g. "2023-2024") that are used when a command's `season` option is left out,
+    /// instead of the current season.
+    pub default_season: Option<String>,
+
+    /// Whether responses are only shown to the user who ran the command.
+    pub ephemeral: bool,
+
+    /// Channels that commands can be used in. Commands can be used in every channel if this is empty.
+    pub allowed_channels: Vec<u64>,
+}
+
+impl GuildConfig {
+    /// Loads a guild's settings, falling back to the defaults for anything that isn't set (or if the
+    /// settings can't be read).
+    pub async fn load(storage: Option<&Storage>, guild_id: Option<u64>) -> Self {
+        let (Some(storage), Some(guild_id)) = (storage, guild_id) else {
+            return Self::default();
+        };
+
+        let settings = storage::run(storage, move |storage| {
+            Ok(Self {
+                default_program: storage
+                    .guild_setting(guild_id, DEFAULT_PROGRAM_KEY)?
+                    .and_then(|value| value.parse().ok()),
+                default_season: storage.guild_setting(guild_id, DEFAULT_SEASON_KEY)?,
+                ephemeral: storage.guild_setting(guild_id, EPHEMERAL_KEY)?.as_deref() == Some("true"),
+                allowed_channels: storage
+                    .guild_setting(guild_id, ALLOWED_CHANNELS_KEY)?
+                    .map(|value| value.split(',').filter_map(|id| id.parse().ok()).collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .await;
+
+        settings.unwrap_or_else(|err| {
+            println!("Failed to load settings for guild {guild_id}: {err}");
+            Self::default()
+        })
+    }
+
+    /// Saves a guild's settings. Settings that are back to their defaults are removed.
+    pub async fn save(&self, storage: &Storage, guild_id: u64) -> Result<(), StorageError> {
+        let settings = [
+            (DEFAULT_PROGRAM_KEY, self.default_program.map(|id| id.to_string())),
+            (DEFAULT_SEASON_KEY, self.default_season.clone()),
+            (EPHEMERAL_KEY, self.ephemeral.then(|| "true".to_string())),
+            (
+                ALLOWED_CHANNELS_KEY,
+                (!self.allowed_channels.is_empty()).then(|| {
+                    self.allowed_channels.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",")
+                }),
+            ),
+        ];
+
+        storage::run(storage, move |storage| {
+            for (key, value) in settings.iter() {
+                match value {
+                    Some(value) => storage.set_guild_setting(guild_id, key, value)?,
+                    None => storage.remove_guild_setting(guild_id, key)?,
+                }
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Returns whether commands can be used in a channel.
+    pub fn allows_channel(&self, channel_id: u64) -> bool {
+        self.allowed_channels.is_empty() || self.allowed_channels.contains(&channel_id)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use serenity::model::Color;
+
+use crate::knowledge::{Article, KnowledgeSource};
+use crate::search::fuzzy_score;
+
+/// Indexes of every knowledge source that ships with the bot.
+const BUNDLED_CATALOGS: &[&str] = &[
+    include_str!("../../assets/knowledge/sigbots.json"),
+    include_str!("../../assets/knowledge/pros.json"),
+];
+
+/// A knowledge source backed by a JSON index of its articles.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct ArticleCatalog {
+    pub id: String,
+    pub name: String,
+
+    /// Color of the source's embeds, as RGB.
+    pub color: (u8, u8, u8),
+
+    pub base_url: String,
+    pub articles: Vec<Article>,
+}
+
+impl ArticleCatalog {
+    /// The catalogs that are compiled into the bot.
+    pub fn bundled() -> Vec<Self> {
+        BUNDLED_CATALOGS
+            .iter()
+            .map(|json| serde_json::from_str(json).expect("Bundled knowledge source index is invalid."))
+            .collect()
+    }
+
+    /// Loads a catalog from a JSON file, so that it can be refreshed without rebuilding the bot.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+impl KnowledgeSource for ArticleCatalog {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn color(&self) -> Color {
+        Color::from_rgb(self.color.0, self.color.1, self.color.2)
+    }
+
+    fn url(&self, article: &Article) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), article.path)
+    }
+
+    /// Matches in an article's title or keywords count for more than matches in its headings, path or summary.
+    fn search(&self, query: &str, limit: usize) -> Vec<(&Article, f32)> {
+        let mut matches = self.articles
+            .iter()
+            .filter_map(|article| {
+                let headings = article.headings.join(" ");
+                let keywords = article.keywords.join(" ");
+                let score = fuzzy_score(query, &[
+                    (&article.title, 4.0),
+                    (&keywords, 4.0),
+                    (&headings, 2.0),
+                    (&article.path, 2.0),
+                    (&article.summary, 1.0),
+                ]);
+
+                (score > 0.0).then_some((article, score))
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.title.cmp(&b.0.title)));
+        matches.truncate(limit);
+        matches
+    }
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+pub mod catalog;
+
+pub use catalog::*;
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+use serenity::model::Color;
+
+/// A single article from a [`KnowledgeSource`].
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct Article {
+    pub title: String,
+
+    /// Path of the article, relative to its source's base URL.
+    pub path: String,
+
+    /// Section headings within the article.
+    #[serde(default)]
+    pub headings: Vec<String>,
+
+    /// Other names that people commonly search for the article by.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+
+    #[serde(default)]
+    pub summary: String,
+}
+
+/// Somewhere that `/wiki` can find articles, such as the Sigbots wiki or the PROS docs.
+///
+/// Sources are searched independently, so each one can keep whatever index suits it. Search scores should be
+/// comparable between sources, since results from every source are ranked against each other.
+pub trait KnowledgeSource: Send + Sync + fmt::Debug {
+    /// Short identifier used to pick the source in `/wiki` (e.g. "sigbots").
+    fn id(&self) -> &str;
+
+    /// Name shown on the source's embeds.
+    fn name(&self) -> &str;
+
+    /// Color of the source's embeds.
+    fn color(&self) -> Color;
+
+    /// Full URL of an article from this source.
+    fn url(&self, article: &Article) -> String;
+
+    /// Returns up to `limit` articles matching a query along with their scores, best matches first.
+    fn search(&self, query: &str, limit: usize) -> Vec<(&Article, f32)>;
+}
+
+/// A search result from a [`KnowledgeBase`], along with the source it came from.
+#[derive(Clone, Debug)]
+pub struct KnowledgeMatch<'a> {
+    pub source: &'a dyn KnowledgeSource,
+    pub article: &'a Article,
+    pub score: f32,
+}
+
+impl KnowledgeMatch<'_> {
+    /// Full URL of the matched article.
+    pub fn url(&self) -> String {
+        self.source.url(self.article)
+    }
+}
+
+/// Every knowledge source that `/wiki` can search.
+#[derive(Debug, Default)]
+pub struct KnowledgeBase {
+    sources: Vec<Box<dyn KnowledgeSource>>,
+}
+
+impl KnowledgeBase {
+    /// Creates a knowledge base from the bundled sources, replacing any of them that have a newer index saved
+    /// as `{id}.json` in `directory`.
+    pub fn load(directory: impl AsRef<Path>) -> Self {
+        let mut knowledge = Self::default();
+
+        for catalog in ArticleCatalog::bundled() {
+            let path = directory.as_ref().join(format!("{}.json", catalog.id));
+            let catalog = if path.exists() {
+                ArticleCatalog::load(&path).unwrap_or_else(|err| {
+                    println!("Failed to load {} index from {}, using the bundled one instead: {err}", catalog.name, path.display());
+                    catalog
+                })
+            } else {
+                catalog
+            };
+
+            knowledge.add(Box::new(catalog));
+        }
+
+        knowledge
+    }
+
+    /// Adds a source, replacing any existing source with the same ID.
+    pub fn add(&mut self, source: Box<dyn KnowledgeSource>) {
+        self.sources.retain(|existing| existing.id() != source.id());
+        self.sources.push(source);
+    }
+
+    /// Returns every source, in the order they were added.
+    pub fn sources(&self) -> impl Iterator<Item = &dyn KnowledgeSource> {
+        self.sources.iter().map(|source| source.as_ref())
+    }
+
+    /// Returns up to `limit` articles matching a query, best matches first.
+    ///
+    /// If `source_id` is `None`, results from every source are merged together by score.
+    pub fn search(&self, query: &str, source_id: Option<&str>, limit: usize) -> Vec<KnowledgeMatch<'_>> {
+        let mut matches = self
+            .sources()
+            .filter(|source| source_id.is_none_or(|id| source.id() == id))
+            .flat_map(|source| {
+                source
+                    .search(query, limit)
+                    .into_iter()
+                    .map(move |(article, score)| KnowledgeMatch { source, article, score })
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(limit);
+        matches
+    }
+}
\ No newline at end of file
@@ -0,0 +1,176 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use serde::{Serialize, Deserialize};
+use tokio::sync::RwLock;
+
+use crate::search::fuzzy_score;
+use crate::storage::{self, Storage, StorageError};
+
+/// A team that autocomplete can suggest.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct IndexedTeam {
+    pub number: String,
+    pub name: String,
+
+    /// Program that the team competes in (e.g. "VRC").
+    pub program: String,
+}
+
+/// An event that autocomplete can suggest.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct IndexedEvent {
+    pub sku: String,
+    pub name: String,
+}
+
+/// Local index of team numbers and event SKUs that the bot has come across, used for autocomplete.
+///
+/// Teams are added from every downloaded skills leaderboard, and events from every event that gets looked up.
+/// Autocomplete responses have to be sent within a few seconds, so suggestions never hit RobotEvents.
+///
+/// If the index is backed by [`Storage`], every new entry is saved so that it survives restarts.
+#[derive(Default, Debug, Clone)]
+pub struct LookupIndex {
+    storage: Option<Storage>,
+
+    /// Teams keyed by uppercase team number and program, since numbers can be reused across programs.
+    teams: Arc<RwLock<BTreeMap<(String, String), IndexedTeam>>>,
+
+    /// Events keyed by uppercase SKU.
+    events: Arc<RwLock<BTreeMap<String, IndexedEvent>>>,
+}
+
+impl LookupIndex {
+    /// Loads every indexed team and event from storage.
+    pub async fn load(storage: Storage) -> Result<Self, StorageError> {
+        let (teams, events) = storage::run(&storage, |storage| Ok((storage.indexed_teams()?, storage.indexed_events()?))).await?;
+
+        Ok(Self {
+            storage: Some(storage),
+            teams: Arc::new(RwLock::new(
+                teams.into_iter().map(|team| ((team.number.to_uppercase(), team.program.clone()), team)).collect(),
+            )),
+            events: Arc::new(RwLock::new(
+                events.into_iter().map(|event| (event.sku.to_uppercase(), event)).collect(),
+            )),
+        })
+    }
+
+    /// Returns whether any teams have been indexed.
+    pub async fn has_teams(&self) -> bool {
+        !self.teams.read().await.is_empty()
+    }
+
+    /// Adds teams to the index. Only teams that are new or have changed are saved to storage.
+    pub async fn add_teams(&self, teams: impl IntoIterator<Item = IndexedTeam>) {
+        let changed = {
+            let mut indexed = self.teams.write().await;
+            teams
+                .into_iter()
+                .filter(|team| {
+                    let key = (team.number.to_uppercase(), team.program.clone());
+                    if indexed.get(&key) == Some(team) {
+                        return false;
+                    }
+
+                    indexed.insert(key, team.clone());
+                    true
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if changed.is_empty() {
+            return;
+        }
+        let Some(storage) = &self.storage else {
+            return;
+        };
+
+        if let Err(err) = storage::run(storage, move |storage| storage.index_teams(&changed)).await {
+            println!("Failed to save team index: {err}");
+        }
+    }
+
+    /// Adds an event to the index.
+    pub async fn add_event(&self, event: IndexedEvent) {
+        {
+            let mut indexed = self.events.write().await;
+            let key = event.sku.to_uppercase();
+            if indexed.get(&key) == Some(&event) {
+                return;
+            }
+
+            indexed.insert(key, event.clone());
+        }
+
+        let Some(storage) = &self.storage else {
+            return;
+        };
+
+        if let Err(err) = storage::run(storage, move |storage| storage.index_event(&event)).await {
+            println!("Failed to save event index: {err}");
+        }
+    }
+
+    /// Returns up to `limit` teams matching a query.
+    ///
+    /// Teams whose numbers start with the query come first (shortest numbers first), followed by teams whose
+    /// names contain it.
+    pub async fn search_teams(&self, query: &str, limit: usize) -> Vec<IndexedTeam> {
+        let query = query.trim().to_uppercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let teams = self.teams.read().await;
+
+        let mut matches = teams
+            .iter()
+            .filter(|((number, _), _)| number.starts_with(&query))
+            .map(|(_, team)| team)
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|team| (team.number.len(), team.number.to_uppercase()));
+        matches.truncate(limit);
+
+        if matches.len() < limit {
+            let lowercase_query = query.to_lowercase();
+            let by_name = teams
+                .iter()
+                .filter(|((number, _), team)| !number.starts_with(&query) && team.name.to_lowercase().contains(&lowercase_query))
+                .map(|(_, team)| team)
+                .take(limit - matches.len())
+                .collect::<Vec<_>>();
+            matches.extend(by_name);
+        }
+
+        matches.into_iter().cloned().collect()
+    }
+
+    /// Returns up to `limit` events matching a query, either by SKU or by name.
+    pub async fn search_events(&self, query: &str, limit: usize) -> Vec<IndexedEvent> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let uppercase_query = query.to_uppercase();
+        let mut matches = self.events
+            .read()
+            .await
+            .iter()
+            .filter_map(|(sku, event)| {
+                let score = if sku.starts_with(&uppercase_query) {
+                    f32::MAX
+                } else {
+                    fuzzy_score(query, &[(&event.name, 1.0)])
+                };
+
+                (score > 0.0).then(|| (score, event.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        // Newer events (with higher SKUs) win ties, since they're the ones most likely being asked about.
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.sku.cmp(&a.1.sku)));
+        matches.into_iter().take(limit).map(|(_, event)| event).collect()
+    }
+}
\ No newline at end of file
@@ -1,11 +1,13 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
+
+use serde::{de::DeserializeOwned, Serialize};
 
 use serenity::{
     prelude::*,
     async_trait,
     all::{Command, Message, GatewayIntents},
     futures::StreamExt,
-    builder::{CreateInteractionResponse, CreateInteractionResponseMessage, CreateEmbedFooter, CreateEmbed, CreateMessage},
+    builder::{CreateInteractionResponse, CreateInteractionResponseMessage, CreateEmbedFooter, CreateEmbed, CreateMessage, EditInteractionResponse},
     model::{
         application::Interaction,
         gateway::Ready, Color,
@@ -13,14 +15,27 @@ use serenity::{
 };
 
 use commands::{
+    CompareCommand,
+    ConfigCommand,
+    DocsCommand,
+    FollowCommand,
+    IamCommand,
+    EventCommand,
     PingCommand,
     PredictCommand,
+    ProgressionCommand,
+    ProjectionsCommand,
+    QnaCommand,
+    RuleCommand,
+    SkillsCommand,
     TeamCommand,
+    UnfollowCommand,
     WikiCommand,
 };
 use api::{
     vrc_data_analysis::VRCDataAnalysis,
     skills::SkillsCache,
+    skills_history::SkillsHistory,
 };
 use robotevents::{
     RobotEvents,
@@ -28,9 +43,28 @@ use robotevents::{
     query::{SeasonsQuery, PaginatedQuery},
 };
 use shuttle_runtime::SecretStore;
+use analytics::SeasonRatingsCache;
+use docs::DocsIndex;
+use guild_config::GuildConfig;
+use knowledge::KnowledgeBase;
+use lookup_index::LookupIndex;
+use qna::QnaArchive;
+use rules::RuleLibrary;
+use storage::{PostgresStorage, SqliteStorage, Storage};
+use watchlist::Watchlist;
 
+mod analytics;
 mod api;
 mod commands;
+mod docs;
+mod guild_config;
+mod knowledge;
+mod lookup_index;
+mod qna;
+mod rules;
+mod search;
+mod storage;
+mod watchlist;
 
 #[derive(Clone, Copy, Default, Debug, PartialEq)]
 pub struct BotRequestError;
@@ -39,6 +73,14 @@ struct Bot {
     robotevents: RobotEvents,
     vrc_data_analysis: VRCDataAnalysis,
     skills_cache: SkillsCache,
+    season_ratings: SeasonRatingsCache,
+    watchlist: Watchlist,
+    lookup_index: LookupIndex,
+    storage: Option<Storage>,
+    qna_archive: Option<QnaArchive>,
+    rules: RuleLibrary,
+    knowledge: KnowledgeBase,
+    docs_index: DocsIndex,
     season_list: Result<PaginatedResponse<Season>, BotRequestError>,
     program_list: Result<PaginatedResponse<IdInfo>, BotRequestError>
 }
@@ -57,44 +99,156 @@ impl EventHandler for Bot {
         }
 
         // Needs to be done in separate calls because of discord request character limit for command registration.
-        Command::create_global_command(&ctx.http, WikiCommand::command()).await.expect("Failed to register wiki command.");
+        Command::create_global_command(&ctx.http, WikiCommand::command(&self.knowledge)).await.expect("Failed to register wiki command.");
         Command::create_global_command(&ctx.http, TeamCommand::command(self.program_list.clone().ok())).await.expect("Failed to register team command.");
         Command::create_global_command(&ctx.http, PingCommand::command()).await.expect("Failed to register ping command.");
         Command::create_global_command(&ctx.http, PredictCommand::command()).await.expect("Failed to register predict command.");
+        Command::create_global_command(&ctx.http, EventCommand::command()).await.expect("Failed to register event command.");
+        Command::create_global_command(&ctx.http, ProjectionsCommand::command()).await.expect("Failed to register projections command.");
+        Command::create_global_command(&ctx.http, SkillsCommand::command(self.program_list.clone().ok())).await.expect("Failed to register skills command.");
+        Command::create_global_command(&ctx.http, ProgressionCommand::command(self.program_list.clone().ok())).await.expect("Failed to register progression command.");
+        Command::create_global_command(&ctx.http, CompareCommand::command(self.program_list.clone().ok())).await.expect("Failed to register compare command.");
+        Command::create_global_command(&ctx.http, FollowCommand::command(self.program_list.clone().ok())).await.expect("Failed to register follow command.");
+        Command::create_global_command(&ctx.http, UnfollowCommand::command()).await.expect("Failed to register unfollow command.");
+        Command::create_global_command(&ctx.http, IamCommand::command(self.program_list.clone().ok())).await.expect("Failed to register iam command.");
+        Command::create_global_command(&ctx.http, ConfigCommand::command(self.program_list.clone().ok())).await.expect("Failed to register config command.");
+        // `/rule` is only offered once rules have been imported. Global commands stay registered across restarts,
+        // so it has to be removed explicitly otherwise.
+        if self.rules.is_empty() {
+            if let Ok(commands) = Command::get_global_commands(&ctx.http).await {
+                for command in commands.into_iter().filter(|command| command.name == "rule") {
+                    Command::delete_global_command(&ctx.http, command.id).await.ok();
+                }
+            }
+        } else {
+            Command::create_global_command(&ctx.http, RuleCommand::command(&self.rules)).await.expect("Failed to register rule command.");
+        }
+        Command::create_global_command(&ctx.http, QnaCommand::command()).await.expect("Failed to register qna command.");
+        Command::create_global_command(&ctx.http, DocsCommand::command()).await.expect("Failed to register docs command.");
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         match interaction {
             Interaction::Command(command) => {
+                let config = GuildConfig::load(self.storage.as_ref(), command.guild_id.map(|id| id.get())).await;
+
+                // Servers can limit the bot to certain channels, but `/config` always works so that admins can't
+                // lock themselves out.
+                if !config.allows_channel(command.channel_id.get()) && command.data.name != "config" {
+                    let allowed_channels = config.allowed_channels.iter().map(|id| format!("<#{id}>")).collect::<Vec<_>>().join(", ");
+                    let response = CreateInteractionResponseMessage::new()
+                        .content(format!("RoboStats can't be used in this channel. Try {allowed_channels}."))
+                        .ephemeral(true);
+
+                    if let Err(error) = command.create_response(&ctx.http, CreateInteractionResponse::Message(response)).await {
+                        println!("Failed to respond to {} command: {error}", command.data.name.as_str());
+                    }
+                    return;
+                }
+
                 // Some commands store persistent data across component interactions, and thus require an
                 // instance to be created for them ahead of time.
                 let mut team_command = TeamCommand::default();
-                let predict_command = PredictCommand::default();
-                let ping_command = PingCommand::default();
-                let wiki_command = WikiCommand::default();
-
-                // Generate a response messaage for a given command type.
-                let response_message = match command.data.name.as_str() {
-                    "ping" => {
-                        ping_command.response()
-                    },
-                    "predict" => {
-                        predict_command.response(&ctx, &command, &self.vrc_data_analysis).await
+                let mut event_command = EventCommand::default();
+                let mut skills_command = SkillsCommand::default();
+                let predict_command = PredictCommand;
+                let projections_command = ProjectionsCommand;
+                let progression_command = ProgressionCommand;
+                let compare_command = CompareCommand;
+                let follow_command = FollowCommand;
+                let unfollow_command = UnfollowCommand;
+                let iam_command = IamCommand;
+                let config_command = ConfigCommand;
+                let rule_command = RuleCommand;
+                let qna_command = QnaCommand;
+                let docs_command = DocsCommand;
+                let ping_command = PingCommand;
+                let wiki_command = WikiCommand;
+
+                // Some commands make dozens of RobotEvents requests or run simulations before they have anything
+                // to show, which can take longer than the 3 seconds Discord gives us to respond. These are deferred
+                // straight away, and their response is sent as an edit once it's ready.
+                let deferred = matches!(command.data.name.as_str(), "team" | "predict" | "projections" | "progression" | "compare" | "skills");
+
+                if deferred {
+                    let defer = CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new().ephemeral(config.ephemeral));
+                    if let Err(error) = command.create_response(&ctx.http, defer).await {
+                        println!("Failed to defer {} command: {error}", command.data.name.as_str());
+                        return;
                     }
-                    "team" => {
-                        team_command.response(&ctx, &command, &self.robotevents, &self.vrc_data_analysis, &self.skills_cache).await
-                    },
-                    "wiki" => {
-                        wiki_command.response(&ctx, &command)
-                    },
-                    _ => {
-                        CreateInteractionResponseMessage::new().content("not implemented :(")
+
+                    let edit = match command.data.name.as_str() {
+                        "predict" => {
+                            predict_command.response(&ctx, &command, &self.robotevents, &self.vrc_data_analysis, &self.season_ratings, self.storage.as_ref(), &config).await
+                        },
+                        "team" => {
+                            team_command.response(&ctx, &command, &self.robotevents, &self.vrc_data_analysis, &self.skills_cache, &self.season_ratings, self.storage.as_ref(), &config).await
+                        },
+                        "projections" => {
+                            projections_command.response(&ctx, &command, &self.robotevents, &self.vrc_data_analysis).await
+                        },
+                        "progression" => {
+                            progression_command.response(&ctx, &command, &self.robotevents, &self.skills_cache, self.season_list.as_ref().ok(), &config).await
+                        },
+                        "compare" => {
+                            compare_command.response(&ctx, &command, &self.robotevents, &self.vrc_data_analysis, &self.skills_cache, self.season_list.as_ref().ok(), &config).await
+                        },
+                        "skills" => {
+                            skills_command.response(&ctx, &command, &self.robotevents, &self.skills_cache, self.season_list.as_ref().ok(), &config).await
+                        },
+                        _ => EditInteractionResponse::new().content("not implemented :("),
+                    };
+
+                    if let Err(error) = command.edit_response(&ctx.http, edit).await {
+                        println!("Failed to respond to {} command: {error}", command.data.name.as_str());
+                    }
+                } else {
+                    // Generate a response messaage for a given command type.
+                    let mut response_message = match command.data.name.as_str() {
+                        "ping" => {
+                            ping_command.response()
+                        },
+                        "wiki" => {
+                            wiki_command.response(&ctx, &command, &self.knowledge)
+                        },
+                        "event" => {
+                            event_command.response(&ctx, &command, &self.robotevents, &self.lookup_index).await
+                        },
+                        "follow" => {
+                            follow_command.response(&ctx, &command, &self.robotevents, &self.watchlist, &config).await
+                        },
+                        "unfollow" => {
+                            unfollow_command.response(&ctx, &command, &self.watchlist).await
+                        },
+                        "iam" => {
+                            iam_command.response(&ctx, &command, &self.robotevents, self.storage.as_ref(), &config).await
+                        },
+                        "config" => {
+                            config_command.response(&ctx, &command, self.storage.as_ref(), self.program_list.as_ref().ok(), self.season_list.as_ref().ok()).await
+                        },
+                        "rule" => {
+                            rule_command.response(&ctx, &command, &self.rules, &config)
+                        },
+                        "qna" => {
+                            qna_command.response(&ctx, &command, self.qna_archive.as_ref())
+                        },
+                        "docs" => {
+                            docs_command.response(&ctx, &command, &self.docs_index)
+                        },
+                        _ => {
+                            CreateInteractionResponseMessage::new().content("not implemented :(")
+                        }
+                    };
+
+                    // Commands that are always private (like `/iam`) set this themselves, so it's only ever turned on here.
+                    if config.ephemeral {
+                        response_message = response_message.ephemeral(true);
+                    }
+
+                    // Send initial response message to user's command.
+                    if let Err(error) = command.create_response(&ctx.http, CreateInteractionResponse::Message(response_message)).await {
+                        println!("Failed to respond to {} command: {error}", command.data.name.as_str());
                     }
-                };
-                
-                // Send initial response message to user's command.
-                if let Err(error) = command.create_response(&ctx.http, CreateInteractionResponse::Message(response_message)).await {
-                    println!("Failed to respond to {} command: {error}", command.data.name.as_str());
                 }
                 
                 // Wait for component interactions and handle them according to the respective command.
@@ -106,16 +260,44 @@ impl EventHandler for Bot {
                     while let Some(component_interaction) = interaction_stream.next().await {
                         match command.data.name.as_str() {
                             "team" => {
+                                if let Some(followup) = team_command.component_interaction_response(&ctx, &command, &component_interaction, &self.robotevents, &self.vrc_data_analysis, &self.skills_cache, &self.season_ratings).await {
+                                    component_interaction.create_followup(&ctx, followup).await.ok();
+                                }
+                            },
+                            "event" => {
                                 component_interaction.create_response(
                                     &ctx,
-                                    team_command.component_interaction_response(&ctx, &command, &component_interaction, &self.robotevents, &self.vrc_data_analysis, &self.skills_cache).await
+                                    event_command.component_interaction_response(&ctx, &command, &component_interaction, &self.robotevents).await
                                 ).await.unwrap_or(());
                             },
+                            "skills" => {
+                                if let Some(followup) = skills_command.component_interaction_response(&ctx, &command, &component_interaction, &self.robotevents, &self.skills_cache).await {
+                                    component_interaction.create_followup(&ctx, followup).await.ok();
+                                }
+                            },
                             _ => {}
                         }
                     }
                 }
-            }
+            },
+            Interaction::Autocomplete(command) => {
+                let config = GuildConfig::load(self.storage.as_ref(), command.guild_id.map(|id| id.get())).await;
+
+                // Suggest values for whichever option the user is currently typing in.
+                let autocomplete_response = match command.data.name.as_str() {
+                    "team" => TeamCommand::team_suggestions(&command, &self.lookup_index, self.storage.as_ref()).await,
+                    "predict" => PredictCommand.autocomplete(&command, &self.lookup_index, self.storage.as_ref(), &config).await,
+                    "event" | "projections" => EventCommand::sku_suggestions(&command, &self.lookup_index, &config).await,
+                    "wiki" => WikiCommand.autocomplete(&command, &self.knowledge),
+                    "rule" => RuleCommand.autocomplete(&command, &self.rules, &config),
+                    "docs" => DocsCommand.autocomplete(&command, &self.docs_index),
+                    _ => return,
+                };
+
+                if let Err(error) = command.create_response(&ctx.http, CreateInteractionResponse::Autocomplete(autocomplete_response)).await {
+                    println!("Failed to autocomplete {} command: {error}", command.data.name.as_str());
+                }
+            },
             _ => {}
         }
     }
@@ -146,6 +328,67 @@ impl EventHandler for Bot {
     }
 }
 
+/// Saves a successful RobotEvents response to storage under `key`, or loads the last saved copy if the
+/// request failed.
+async fn cached_response<T: Serialize + DeserializeOwned + Send + 'static>(
+    storage: Option<&Storage>,
+    key: &'static str,
+    response: Result<T, reqwest::Error>,
+) -> Result<T, BotRequestError> {
+    let Some(storage) = storage else {
+        return response.map_err(|_| BotRequestError);
+    };
+
+    match response {
+        Ok(response) => {
+            if let Ok(value) = serde_json::to_string(&response) {
+                if let Err(err) = storage::run(storage, move |storage| storage.cache_response(key, &value)).await {
+                    println!("Failed to save {key} list: {err}");
+                }
+            }
+            Ok(response)
+        },
+        Err(_) => {
+            let cached = storage::run(storage, move |storage| storage.cached_response(key)).await;
+            cached
+                .ok()
+                .flatten()
+                .and_then(|cached| serde_json::from_str(&cached.value).ok())
+                .ok_or(BotRequestError)
+        },
+    }
+}
+
+/// Downloads the current skills leaderboards for VRC, VEXU and VIQC in the background, so that team number
+/// autocomplete works on a fresh install before anyone has looked at a leaderboard.
+fn spawn_lookup_index_seeding(robotevents: RobotEvents, skills_cache: SkillsCache, seasons: &[Season]) {
+    let leaderboards = [
+        (1, vec!["High School", "Middle School"]), // VRC
+        (4, vec!["College"]), // VEXU
+        (41, vec!["Middle School", "Elementary School"]), // VIQC
+    ]
+    .into_iter()
+    .filter_map(|(program_id, grades)| {
+        let season = seasons
+            .iter()
+            .filter(|season| season.program.id == program_id)
+            .max_by_key(|season| season.years_start)?;
+
+        Some((season.id, grades))
+    })
+    .collect::<Vec<_>>();
+
+    tokio::spawn(async move {
+        for (season_id, grades) in leaderboards {
+            for grade in grades {
+                if let Err(err) = skills_cache.get_rankings(season_id, grade, false, &robotevents).await {
+                    println!("Failed to fetch {grade} skills leaderboard for autocomplete: {err}");
+                }
+            }
+        }
+    });
+}
+
 #[shuttle_runtime::main]
 async fn serenity(
     #[shuttle_runtime::Secrets] secrets: SecretStore,
@@ -161,19 +404,160 @@ async fn serenity(
     let robotevents = RobotEvents::new(robotevents_token);
     let vrc_data_analysis = VRCDataAnalysis::new();
 
+    // Bot state that needs to survive restarts is kept in a SQLite database in `DATA_DIRECTORY`. Setting `STORAGE`
+    // to `postgres` keeps it in the PostgreSQL database at `DATABASE_URL` instead, for hosts where files written by
+    // the bot don't persist. If storage can't be opened, the bot still runs, but without any of the features that
+    // depend on it.
+    let data_directory = std::path::PathBuf::from(secrets.get("DATA_DIRECTORY").unwrap_or_else(|| "data".to_string()));
+    let opened_storage: Result<Storage, String> = if secrets.get("STORAGE").as_deref() == Some("postgres") {
+        match secrets.get("DATABASE_URL") {
+            // The PostgreSQL client blocks, so it can't connect from the async runtime.
+            Some(database_url) => match tokio::task::spawn_blocking(move || PostgresStorage::connect(&database_url)).await {
+                Ok(storage) => storage.map(|storage| Arc::new(storage) as Storage).map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            },
+            None => Err("STORAGE is set to postgres, but DATABASE_URL is missing.".to_string()),
+        }
+    } else {
+        std::fs::create_dir_all(&data_directory)
+            .map_err(|err| err.to_string())
+            .and_then(|_| SqliteStorage::open(data_directory.join("robostats.db")).map_err(|err| err.to_string()))
+            .map(|storage| Arc::new(storage) as Storage)
+    };
+    let storage: Option<Storage> = match opened_storage {
+        Ok(storage) => Some(storage),
+        Err(err) => {
+            println!("Failed to open database. Bot state won't be saved between restarts: {err}");
+            None
+        },
+    };
+
+    // Team numbers and event SKUs that the bot has seen are indexed for autocomplete.
+    let lookup_index = match &storage {
+        Some(storage) => LookupIndex::load(storage.clone()).await.unwrap_or_else(|err| {
+            println!("Failed to load saved autocomplete index: {err}");
+            LookupIndex::default()
+        }),
+        None => LookupIndex::default(),
+    };
+
+    // Every downloaded skills leaderboard is saved as a snapshot for charting progression over a season, and
+    // its teams are added to the autocomplete index.
+    let mut skills_cache = match &storage {
+        Some(storage) => SkillsCache::with_history(SkillsHistory::new(storage.clone())),
+        None => SkillsCache::default(),
+    };
+    skills_cache.set_lookup_index(lookup_index.clone());
+
+    // Optionally keep recently-viewed skills leaderboards warm by refreshing them in the background.
+    if let Some(minutes) = secrets.get("SKILLS_REFRESH_MINUTES").and_then(|minutes| minutes.parse::<u64>().ok()) {
+        skills_cache.spawn_refresh_task(robotevents.clone(), Duration::from_secs(minutes * 60));
+    }
+
+    let watchlist = match &storage {
+        Some(storage) => Watchlist::load(storage.clone()).await.unwrap_or_else(|err| {
+            println!("Failed to load saved watchlist: {err}");
+            Watchlist::default()
+        }),
+        None => Watchlist::default(),
+    };
+
+    // The Q&A archive is imported from an exported JSON snapshot, so that it can be searched without hitting
+    // RobotEvents. The bot still runs without one, but `/qna` won't work.
+    let qna_path = secrets
+        .get("QNA_ARCHIVE")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| data_directory.join("qna.json"));
+    let qna_archive = match QnaArchive::load(&qna_path) {
+        Ok(archive) => Some(archive),
+        Err(err) => {
+            println!("Failed to load Q&A archive from {}: {err}", qna_path.display());
+            None
+        },
+    };
+
+    // Rule books for `/rule` are imported from the game manuals into the data directory. Without any, the
+    // command isn't registered.
+    let rules_path = data_directory.join("rules");
+    let rules = match RuleLibrary::load(&rules_path) {
+        Ok(rules) => rules,
+        Err(err) => {
+            println!("Failed to load rule books from {}: {err}", rules_path.display());
+            RuleLibrary::default()
+        },
+    };
+
+    // Use newer copies of the `/wiki` indexes if any have been saved to the data directory, so they can be
+    // refreshed without a rebuild.
+    let knowledge = KnowledgeBase::load(data_directory.join("knowledge"));
+
+    // Fetch a list of all seasons and programs from RobotEvents, falling back to the copies saved last time
+    // if RobotEvents is down.
+    // We store these as Result<T, E> internally so HTTP fails don't prevent the bot from starting.
+    let program_list = cached_response(storage.as_ref(), "programs", robotevents.programs().await).await;
+    let season_list = cached_response(storage.as_ref(), "seasons", robotevents.seasons(SeasonsQuery::default().per_page(250)).await).await;
+
+    // Season-wide TrueSkill ratings for the current VRC, VEXU and VIQC seasons are rebuilt in the background
+    // (every 12 hours unless configured otherwise), so that locally computed ratings come with a world ranking.
+    let season_ratings = SeasonRatingsCache::default();
+    if let Ok(season_list) = &season_list {
+        let season_ids = [1, 4, 41]
+            .into_iter()
+            .filter_map(|program_id| {
+                season_list.data
+                    .iter()
+                    .filter(|season| season.program.id == program_id)
+                    .max_by_key(|season| season.years_start)
+                    .map(|season| season.id)
+            })
+            .collect::<Vec<_>>();
+        let refresh_hours = secrets
+            .get("SEASON_RATINGS_REFRESH_HOURS")
+            .and_then(|hours| hours.parse::<u64>().ok())
+            .unwrap_or(12);
+
+        season_ratings.spawn_refresh_task(robotevents.clone(), season_ids, Duration::from_secs(refresh_hours * 60 * 60));
+    }
+
     // Build client with token and guild messages intent
     let client = Client::builder(discord_token, GatewayIntents::GUILD_MESSAGES)
         .event_handler(Bot {
-            // Fetch a list of all seasons and programs from RobotEvents
-            // We store these as Result<T, E> internally so HTTP fails don't prevent the bot from starting.
-            program_list: robotevents.programs().await.map_err(|_| BotRequestError),
-            season_list: robotevents.seasons(SeasonsQuery::default().per_page(250)).await.map_err(|_| BotRequestError),
-            robotevents,
+            program_list,
+            season_list: season_list.clone(),
+            robotevents: robotevents.clone(),
             vrc_data_analysis,
-            skills_cache: SkillsCache::default(),
+            skills_cache: skills_cache.clone(),
+            season_ratings,
+            watchlist: watchlist.clone(),
+            lookup_index: lookup_index.clone(),
+            storage,
+            qna_archive,
+            rules,
+            knowledge,
+            docs_index: DocsIndex::lemlib(),
         })
         .await
         .expect("Error creating client");
 
+    // Fill the autocomplete index in the background if it's empty, such as on the bot's first start.
+    if let Ok(season_list) = &season_list {
+        if !lookup_index.has_teams().await {
+            spawn_lookup_index_seeding(robotevents.clone(), skills_cache.clone(), &season_list.data);
+        }
+    }
+
+    // Check followed teams for updates in the background (every 15 minutes unless configured otherwise).
+    let poll_minutes = secrets
+        .get("WATCHLIST_POLL_MINUTES")
+        .and_then(|minutes| minutes.parse::<u64>().ok())
+        .unwrap_or(15);
+    watchlist.spawn_poller(
+        client.http.clone(),
+        robotevents,
+        skills_cache,
+        season_list.map(|season_list| season_list.data).unwrap_or_default(),
+        Duration::from_secs(poll_minutes * 60),
+    );
+
     Ok(client.into())
 }
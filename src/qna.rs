@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Words that are too common in Q&A posts to say anything about what a question is about.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "can", "do", "does", "for", "from", "if", "in",
+    "is", "it", "its", "of", "on", "or", "so", "that", "the", "their", "then", "there", "this", "to", "was",
+    "we", "what", "when", "which", "will", "with", "would", "you",
+];
+
+/// Matches in a question's title count for more than matches in its body or answer.
+const TITLE_WEIGHT: f32 = 3.0;
+
+/// A single answered question from the official Q&A.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct QnaEntry {
+    /// Question number on RobotEvents.
+    pub id: i32,
+    pub title: String,
+    pub question: String,
+    pub answer: String,
+
+    /// Date that the question was answered on (e.g. "2023-08-14").
+    #[serde(default)]
+    pub answered_at: Option<String>,
+
+    pub url: String,
+}
+
+/// An exported copy of a season's Q&A, as imported from a JSON file.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct QnaSnapshot {
+    /// Season years (e.g. "2023-2024").
+    pub season: String,
+
+    /// Name of the season's game.
+    pub game: String,
+
+    /// Date that the snapshot was exported on.
+    #[serde(default)]
+    pub exported_at: Option<String>,
+
+    pub questions: Vec<QnaEntry>,
+}
+
+/// A Q&A search result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QnaMatch<'a> {
+    pub entry: &'a QnaEntry,
+    pub score: f32,
+}
+
+/// Full-text index over a [`QnaSnapshot`].
+///
+/// Questions are searched entirely in memory, so lookups don't depend on RobotEvents being reachable.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct QnaArchive {
+    snapshot: QnaSnapshot,
+
+    /// Maps each word to the questions it appears in, along with how heavily it appears in each of them.
+    index: HashMap<String, Vec<(usize, f32)>>,
+}
+
+impl QnaArchive {
+    /// Indexes every question in a snapshot.
+    pub fn new(snapshot: QnaSnapshot) -> Self {
+        let mut index: HashMap<String, Vec<(usize, f32)>> = HashMap::new();
+
+        for (position, entry) in snapshot.questions.iter().enumerate() {
+            let mut weights: HashMap<String, f32> = HashMap::new();
+            for token in tokenize(&entry.title) {
+                *weights.entry(token).or_default() += TITLE_WEIGHT;
+            }
+            for token in tokenize(&entry.question).chain(tokenize(&entry.answer)) {
+                *weights.entry(token).or_default() += 1.0;
+            }
+
+            for (token, weight) in weights {
+                index.entry(token).or_default().push((position, weight));
+            }
+        }
+
+        Self { snapshot, index }
+    }
+
+    /// Loads and indexes a snapshot from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+
+        Ok(Self::new(serde_json::from_str(&json)?))
+    }
+
+    /// The snapshot that this archive was built from.
+    pub fn snapshot(&self) -> &QnaSnapshot {
+        &self.snapshot
+    }
+
+    /// Returns up to `limit` questions matching a query, best matches first.
+    ///
+    /// Questions are scored by TF-IDF, so rare words in the query (like rule IDs) count for more than
+    /// common ones. Questions matching more of the query's words are ranked higher.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<QnaMatch<'_>> {
+        let mut tokens = tokenize(query).collect::<Vec<_>>();
+        tokens.sort();
+        tokens.dedup();
+
+        let total = self.snapshot.questions.len() as f32;
+        let mut scores: HashMap<usize, (f32, usize)> = HashMap::new();
+
+        for token in tokens.iter() {
+            let Some(postings) = self.index.get(token) else {
+                continue;
+            };
+
+            let idf = (total / postings.len() as f32).ln() + 1.0;
+            for (position, weight) in postings {
+                let score = scores.entry(*position).or_default();
+                score.0 += weight.sqrt() * idf;
+                score.1 += 1;
+            }
+        }
+
+        let mut matches = scores
+            .into_iter()
+            .map(|(position, (score, matched))| QnaMatch {
+                entry: &self.snapshot.questions[position],
+                score: score * matched as f32 / tokens.len() as f32,
+            })
+            .collect::<Vec<_>>();
+
+        // Newer questions (with higher IDs) win ties, since they're more likely to reflect the current manual.
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.entry.id.cmp(&a.entry.id)));
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Splits text into lowercase words, skipping stop words. Rule IDs like "<SG3>" come out as "sg3".
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 1)
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+}
+
+/// Returns a section of `text` of around `length` characters, starting a little before the first word that
+/// matches `query`.
+pub fn excerpt(text: &str, query: &str, length: usize) -> String {
+    let tokens = tokenize(query).collect::<Vec<_>>();
+    let words = text.split_whitespace().collect::<Vec<_>>();
+
+    let first_match = words
+        .iter()
+        .position(|word| tokenize(word).any(|token| tokens.contains(&token)))
+        .unwrap_or_default();
+    let start = first_match.saturating_sub(12);
+
+    let mut excerpt = String::new();
+    for word in &words[start..] {
+        if excerpt.chars().count() + word.chars().count() > length {
+            excerpt.push('…');
+            break;
+        }
+        if !excerpt.is_empty() {
+            excerpt.push(' ');
+        }
+        excerpt.push_str(word);
+    }
+
+    if start > 0 {
+        format!("…{excerpt}")
+    } else {
+        excerpt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> QnaArchive {
+        QnaArchive::new(serde_json::from_str(include_str!("../tests/fixtures/qna.json")).unwrap())
+    }
+
+    #[test]
+    fn search_finds_rule_ids() {
+        let archive = fixture();
+        let results = archive.search("<SG3> match", 5);
+
+        assert!(results.len() > 1);
+        assert_eq!(results[0].entry.id, 1001);
+    }
+
+    #[test]
+    fn search_ranks_title_matches_first() {
+        let archive = fixture();
+        let results = archive.search("starting size", 5);
+
+        assert_eq!(results[0].entry.id, 1002);
+    }
+
+    #[test]
+    fn search_prefers_newer_questions_on_ties() {
+        let archive = fixture();
+        let results = archive.search("barrier triball", 5);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].score, results[1].score);
+        assert_eq!(results[0].entry.id, 1020);
+        assert_eq!(results[1].entry.id, 1010);
+    }
+
+    #[test]
+    fn search_respects_limit() {
+        let archive = fixture();
+
+        assert_eq!(archive.search("match robot", 1).len(), 1);
+        assert!(archive.search("the", 5).is_empty());
+    }
+
+    #[test]
+    fn excerpt_starts_near_first_match() {
+        let text = (1..=40).map(|n| format!("word{n}")).collect::<Vec<_>>().join(" ");
+        let excerpt = excerpt(&text, "word30", 1000);
+
+        assert!(excerpt.starts_with("…word18 "));
+        assert!(excerpt.ends_with("word40"));
+    }
+
+    #[test]
+    fn excerpt_truncates_to_length() {
+        let excerpt = excerpt("one two three four five", "missing", 10);
+
+        assert_eq!(excerpt, "one two…");
+    }
+}
@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// A single rule from a game manual.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct Rule {
+    /// Rule ID without the surrounding angle brackets (e.g. "SG3").
+    pub id: String,
+    pub title: String,
+    pub text: String,
+
+    /// Manual section that the rule is listed under (e.g. "Safety Rules").
+    #[serde(default)]
+    pub section: Option<String>,
+
+    /// Notes on how violations of the rule are penalized, if the manual gives any beyond the standard
+    /// penalties.
+    #[serde(default)]
+    pub violation: Option<String>,
+
+    /// Page of the manual PDF that the rule is on.
+    #[serde(default)]
+    pub page: Option<u32>,
+}
+
+impl Rule {
+    /// The rule's ID, formatted the way the manual writes it (e.g. "<SG3>").
+    pub fn tag(&self) -> String {
+        format!("<{}>", self.id)
+    }
+}
+
+/// Every rule from one season's game manual.
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct RuleBook {
+    /// Season years (e.g. "2023-2024").
+    pub season: String,
+
+    /// Name of the season's game.
+    pub game: String,
+
+    /// Manual version that the rules were copied from.
+    pub version: String,
+
+    pub manual_url: String,
+    pub rules: Vec<Rule>,
+}
+
+impl RuleBook {
+    /// Finds a rule by ID. IDs are matched case-insensitively, with or without angle brackets.
+    pub fn rule(&self, id: &str) -> Option<&Rule> {
+        let id = normalize_id(id);
+        self.rules.iter().find(|rule| rule.id.eq_ignore_ascii_case(&id))
+    }
+
+    /// Returns up to `limit` rules matching a query, best matches first.
+    ///
+    /// Rules whose IDs start with the query are ranked above rules whose IDs or titles contain it. An
+    /// empty query returns rules in manual order.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&Rule> {
+        let id_query = normalize_id(query).to_lowercase();
+        let query = query.trim().to_lowercase();
+
+        let mut matches = self.rules
+            .iter()
+            .enumerate()
+            .filter_map(|(index, rule)| {
+                let id = rule.id.to_lowercase();
+                let score = if id.starts_with(&id_query) {
+                    0
+                } else if id.contains(&id_query) {
+                    1
+                } else if rule.title.to_lowercase().contains(&query) {
+                    2
+                } else {
+                    return None;
+                };
+
+                Some((score, index, rule))
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by_key(|(score, index, _)| (*score, *index));
+        matches.into_iter().take(limit).map(|(_, _, rule)| rule).collect()
+    }
+
+    /// Link to a rule in the game manual, pointing at its page if it's known.
+    pub fn manual_link(&self, rule: &Rule) -> String {
+        match rule.page {
+            Some(page) => format!("{}#page={page}", self.manual_url),
+            None => self.manual_url.clone(),
+        }
+    }
+}
+
+/// Rule books imported from game manuals, one per season.
+///
+/// Rule books are JSON files generated from a manual with `scripts/import_rules.py`. They're read from a
+/// directory at startup rather than compiled in, so a manual update only needs a re-import.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct RuleLibrary {
+    /// Every loaded rule book, newest season first.
+    books: Vec<RuleBook>,
+}
+
+impl RuleLibrary {
+    pub fn new(mut books: Vec<RuleBook>) -> Self {
+        // Newest season first, so that it's picked when no season is given.
+        books.sort_by(|a, b| b.season.cmp(&a.season));
+
+        Self { books }
+    }
+
+    /// Loads every rule book (`*.json`) in a directory.
+    pub fn load(directory: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut books = Vec::new();
+
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                books.push(serde_json::from_str(&std::fs::read_to_string(&path)?)?);
+            }
+        }
+
+        Ok(Self::new(books))
+    }
+
+    /// Returns whether any rule books have been loaded.
+    pub fn is_empty(&self) -> bool {
+        self.books.is_empty()
+    }
+
+    /// Returns every rule book, newest season first.
+    pub fn books(&self) -> &[RuleBook] {
+        &self.books
+    }
+
+    /// Returns the rule book for a season, or the newest one if `season` is `None`.
+    pub fn for_season(&self, season: Option<&str>) -> Option<&RuleBook> {
+        match season {
+            Some(season) => self.books.iter().find(|book| book.season == season),
+            None => self.books.first(),
+        }
+    }
+}
+
+/// Strips angle brackets and whitespace from a rule ID, so that "<sg3>", "SG3" and " SG 3" all match.
+fn normalize_id(id: &str) -> String {
+    id.chars().filter(|c| !c.is_whitespace() && *c != '<' && *c != '>').collect::<String>().to_uppercase()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> RuleLibrary {
+        RuleLibrary::new(vec![
+            serde_json::from_str(include_str!("../tests/fixtures/rules.json")).unwrap(),
+            RuleBook { season: "2022-2023".to_string(), ..Default::default() },
+        ])
+    }
+
+    #[test]
+    fn newest_season_is_the_default() {
+        let library = library();
+
+        assert_eq!(library.for_season(None).unwrap().season, "2023-2024");
+        assert_eq!(library.for_season(Some("2022-2023")).unwrap().season, "2022-2023");
+        assert!(library.for_season(Some("2019-2020")).is_none());
+    }
+
+    #[test]
+    fn finds_rules_with_or_without_brackets() {
+        let library = library();
+        let book = library.for_season(None).unwrap();
+
+        assert_eq!(book.rule("<sg3>").unwrap().id, "SG3");
+        assert_eq!(book.rule(" SG 3 ").unwrap().id, "SG3");
+        assert!(book.rule("SG30").is_none());
+    }
+
+    #[test]
+    fn search_ranks_id_prefixes_first() {
+        let library = library();
+        let book = library.for_season(None).unwrap();
+
+        let ids = book.search("S", 10).iter().map(|rule| rule.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["S1", "SG1", "SG3", "R4"]);
+
+        let ids = book.search("expansion", 10).iter().map(|rule| rule.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["SG3"]);
+    }
+
+    #[test]
+    fn links_to_the_rules_page() {
+        let library = library();
+        let book = library.for_season(None).unwrap();
+
+        assert_eq!(book.manual_link(book.rule("SG3").unwrap()), "https://example.com/manual.pdf#page=23");
+        assert_eq!(book.manual_link(book.rule("S1").unwrap()), "https://example.com/manual.pdf");
+    }
+}
@@ -0,0 +1,74 @@
+/// Splits text into lowercase words.
+pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+}
+
+/// Levenshtein distance between two words, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Scores how well a single query word matches a word from a document, from 0 (no match) to 1 (exact).
+///
+/// Prefixes count as partial matches so that results show up while a word is still being typed, and small
+/// typos are tolerated in longer words.
+pub fn fuzzy_match(query: &str, word: &str) -> f32 {
+    if query == word {
+        return 1.0;
+    }
+    if query.len() >= 2 && word.starts_with(query) {
+        return 0.8;
+    }
+
+    let allowed_typos = match query.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    };
+    match edit_distance(query, word) {
+        distance if distance <= allowed_typos => 0.6 / distance as f32,
+        _ => 0.0,
+    }
+}
+
+/// Scores a document against a query, given the document's fields and how much each one counts for.
+///
+/// Each query word is matched against its best word across every field, so documents only need to match
+/// every query word once. Returns 0 if nothing matches.
+pub fn fuzzy_score(query: &str, fields: &[(&str, f32)]) -> f32 {
+    let query_words = words(query).collect::<Vec<_>>();
+    if query_words.is_empty() {
+        return 0.0;
+    }
+
+    let mut total = 0.0;
+    let mut matched = 0;
+    for query_word in query_words.iter() {
+        let best = fields
+            .iter()
+            .flat_map(|(text, weight)| words(text).map(move |word| fuzzy_match(query_word, &word) * weight))
+            .fold(0.0, f32::max);
+
+        if best > 0.0 {
+            total += best;
+            matched += 1;
+        }
+    }
+
+    // Documents that only match some of the query are ranked below ones that match all of it.
+    total * matched as f32 / query_words.len() as f32
+}
\ No newline at end of file
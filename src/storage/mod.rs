@@ -0,0 +1,158 @@
+pub mod postgresql;
+pub mod sqlite;
+
+pub use postgresql::*;
+pub use sqlite::*;
+
+use std::{fmt, sync::Arc};
+
+use crate::api::skills_history::{SkillsHistoryPoint, SkillsSnapshot};
+use crate::lookup_index::{IndexedEvent, IndexedTeam};
+use crate::watchlist::WatchedTeam;
+
+/// Errors that can occur while reading from or writing to storage.
+#[derive(Debug)]
+pub enum StorageError {
+    /// The SQLite database returned an error.
+    Database(rusqlite::Error),
+
+    /// The PostgreSQL database returned an error.
+    Postgres(postgres::Error),
+
+    /// A stored value couldn't be converted to or from JSON.
+    Serialization(serde_json::Error),
+
+    /// The blocking task running the query panicked or was cancelled.
+    Task(tokio::task::JoinError),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(err) => write!(f, "database error: {err}"),
+            Self::Postgres(err) => write!(f, "database error: {err}"),
+            Self::Serialization(err) => write!(f, "serialization error: {err}"),
+            Self::Task(err) => write!(f, "storage task failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(err: rusqlite::Error) -> Self {
+        Self::Database(err)
+    }
+}
+
+impl From<postgres::Error> for StorageError {
+    fn from(err: postgres::Error) -> Self {
+        Self::Postgres(err)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Serialization(err)
+    }
+}
+
+/// A Discord user's linked RoboStats team.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct UserLink {
+    pub user_id: u64,
+    pub team_id: i32,
+    pub team_number: String,
+    pub program_id: i32,
+}
+
+/// A previously cached API response, stored as JSON.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct CachedResponse {
+    pub value: String,
+
+    /// Unix timestamp (in seconds) of when the response was cached.
+    pub fetched_at: i64,
+}
+
+/// Everything that RoboStats keeps between restarts and redeploys.
+///
+/// This is implemented on top of PostgreSQL by [`PostgresStorage`] (used on Shuttle, where the database is
+/// kept between redeploys) and on top of an embedded SQLite database by [`SqliteStorage`]. Methods are
+/// blocking, so async code should call them through [`run`].
+pub trait Repository: Send + Sync + fmt::Debug {
+    /// Returns a guild setting by key, or `None` if it hasn't been set.
+    fn guild_setting(&self, guild_id: u64, key: &str) -> Result<Option<String>, StorageError>;
+
+    /// Sets a guild setting, replacing any previous value.
+    fn set_guild_setting(&self, guild_id: u64, key: &str, value: &str) -> Result<(), StorageError>;
+
+    /// Removes a guild setting, reverting it to its default.
+    fn remove_guild_setting(&self, guild_id: u64, key: &str) -> Result<(), StorageError>;
+
+    /// Returns the team that a Discord user has linked to themselves.
+    fn user_link(&self, user_id: u64) -> Result<Option<UserLink>, StorageError>;
+
+    /// Links a Discord user to a team, replacing any previous link.
+    fn set_user_link(&self, link: &UserLink) -> Result<(), StorageError>;
+
+    /// Removes a Discord user's team link.
+    ///
+    /// Returns `false` if the user didn't have one.
+    fn remove_user_link(&self, user_id: u64) -> Result<bool, StorageError>;
+
+    /// Returns every followed team from every guild, along with the ID of the guild following it.
+    fn watched_teams(&self) -> Result<Vec<(u64, WatchedTeam)>, StorageError>;
+
+    /// Adds a team to a guild's watchlist, or updates it if the guild already follows it.
+    fn save_watched_team(&self, guild_id: u64, watched: &WatchedTeam) -> Result<(), StorageError>;
+
+    /// Removes a team from a guild's watchlist.
+    fn remove_watched_team(&self, guild_id: u64, team_id: i32) -> Result<(), StorageError>;
+
+    /// Returns a cached API response by key.
+    fn cached_response(&self, key: &str) -> Result<Option<CachedResponse>, StorageError>;
+
+    /// Caches an API response, replacing any previous response with the same key.
+    fn cache_response(&self, key: &str, value: &str) -> Result<(), StorageError>;
+
+    /// Saves a snapshot of a skills leaderboard.
+    fn save_skills_snapshot(&self, season_id: i32, grade: &str, post_season: bool, snapshot: &SkillsSnapshot) -> Result<(), StorageError>;
+
+    /// Returns every snapshotted leaderboard entry for a team on a leaderboard, oldest first.
+    fn team_skills_history(&self, team_id: i32, season_id: i32, grade: &str, post_season: bool) -> Result<Vec<SkillsHistoryPoint>, StorageError>;
+
+    /// Returns the most recently snapshotted entry of every team on a leaderboard.
+    fn latest_skills_entries(&self, season_id: i32, grade: &str, post_season: bool) -> Result<Vec<SkillsHistoryPoint>, StorageError>;
+
+    /// Deletes every skills snapshot taken before a Unix timestamp (in seconds), along with its entries.
+    fn prune_skills_snapshots(&self, taken_before: i64) -> Result<(), StorageError>;
+
+    /// Returns every team in the autocomplete index.
+    fn indexed_teams(&self) -> Result<Vec<IndexedTeam>, StorageError>;
+
+    /// Adds teams to the autocomplete index, replacing any that are already in it.
+    fn index_teams(&self, teams: &[IndexedTeam]) -> Result<(), StorageError>;
+
+    /// Returns every event in the autocomplete index.
+    fn indexed_events(&self) -> Result<Vec<IndexedEvent>, StorageError>;
+
+    /// Adds an event to the autocomplete index, replacing it if it's already in it.
+    fn index_event(&self, event: &IndexedEvent) -> Result<(), StorageError>;
+}
+
+/// Shared handle to the bot's storage.
+pub type Storage = Arc<dyn Repository>;
+
+/// Runs blocking repository calls on a background thread, so they don't stall the async runtime.
+pub async fn run<T, F>(storage: &Storage, f: F) -> Result<T, StorageError>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn Repository) -> Result<T, StorageError> + Send + 'static,
+{
+    let storage = storage.clone();
+
+    tokio::task::spawn_blocking(move || f(storage.as_ref()))
+        .await
+        .map_err(StorageError::Task)?
+}
\ No newline at end of file
@@ -0,0 +1,381 @@
+use std::{
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use postgres::{Client, NoTls, Row};
+
+use crate::api::skills_history::{SkillsHistoryPoint, SkillsSnapshot, SkillsSnapshotEntry};
+use crate::lookup_index::{IndexedEvent, IndexedTeam};
+use crate::storage::{CachedResponse, Repository, StorageError, UserLink};
+use crate::watchlist::WatchedTeam;
+
+/// Schema for every table, applied when the database is connected to.
+///
+/// This mirrors the SQLite schema in [`crate::storage::SqliteStorage`]. Tables are only ever added to, so
+/// `CREATE TABLE IF NOT EXISTS` is enough to bring an older database up to date.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS guild_settings (
+        guild_id BIGINT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (guild_id, key)
+    );
+
+    CREATE TABLE IF NOT EXISTS user_links (
+        user_id BIGINT PRIMARY KEY,
+        team_id INTEGER NOT NULL,
+        team_number TEXT NOT NULL,
+        program_id INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS watched_teams (
+        guild_id BIGINT NOT NULL,
+        team_id INTEGER NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (guild_id, team_id)
+    );
+
+    CREATE TABLE IF NOT EXISTS api_cache (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        fetched_at BIGINT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS skills_snapshots (
+        id BIGSERIAL PRIMARY KEY,
+        season_id INTEGER NOT NULL,
+        grade TEXT NOT NULL,
+        post_season BOOLEAN NOT NULL,
+        taken_at BIGINT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS skills_snapshot_entries (
+        snapshot_id BIGINT NOT NULL REFERENCES skills_snapshots (id) ON DELETE CASCADE,
+        team_id INTEGER NOT NULL,
+        rank INTEGER NOT NULL,
+        max_driver INTEGER NOT NULL,
+        max_programming INTEGER NOT NULL,
+        score INTEGER NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS skills_snapshot_entries_team ON skills_snapshot_entries (team_id, snapshot_id);
+    CREATE INDEX IF NOT EXISTS skills_snapshot_entries_snapshot ON skills_snapshot_entries (snapshot_id);
+    CREATE INDEX IF NOT EXISTS skills_snapshots_taken_at ON skills_snapshots (taken_at);
+
+    CREATE TABLE IF NOT EXISTS team_index (
+        number TEXT NOT NULL,
+        program TEXT NOT NULL,
+        name TEXT NOT NULL,
+        PRIMARY KEY (number, program)
+    );
+
+    CREATE TABLE IF NOT EXISTS event_index (
+        sku TEXT PRIMARY KEY,
+        name TEXT NOT NULL
+    );
+";
+
+/// [`Repository`] implementation backed by a PostgreSQL database, such as the one Shuttle provisions.
+///
+/// Unlike a local SQLite file, a Shuttle database is kept between redeploys.
+///
+/// The client is blocking and runs its own runtime internally, so it must only be used from blocking
+/// threads (which [`crate::storage::run`] already ensures), and connected to through
+/// [`tokio::task::spawn_blocking`].
+pub struct PostgresStorage {
+    client: Mutex<Client>,
+}
+
+impl std::fmt::Debug for PostgresStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresStorage").finish_non_exhaustive()
+    }
+}
+
+impl PostgresStorage {
+    /// Connects to the database at `url`, and makes sure that every table exists.
+    pub fn connect(url: &str) -> Result<Self, StorageError> {
+        let mut client = Client::connect(url, NoTls)?;
+        client.batch_execute(SCHEMA)?;
+
+        Ok(Self {
+            client: Mutex::new(client),
+        })
+    }
+
+    /// Locks the database client.
+    ///
+    /// A poisoned lock only means that another query panicked, so the client is still handed out.
+    fn client(&self) -> std::sync::MutexGuard<'_, Client> {
+        self.client.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Returns the current time as a Unix timestamp in seconds.
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs() as i64).unwrap_or_default()
+}
+
+/// Reads a skills history point from a row of `taken_at, team_id, rank, max_driver, max_programming, score`.
+fn skills_history_point(row: &Row) -> Result<SkillsHistoryPoint, postgres::Error> {
+    Ok(SkillsHistoryPoint {
+        taken_at: row.try_get(0)?,
+        entry: SkillsSnapshotEntry {
+            team_id: row.try_get(1)?,
+            rank: row.try_get(2)?,
+            max_driver: row.try_get(3)?,
+            max_programming: row.try_get(4)?,
+            score: row.try_get(5)?,
+        },
+    })
+}
+
+impl Repository for PostgresStorage {
+    fn guild_setting(&self, guild_id: u64, key: &str) -> Result<Option<String>, StorageError> {
+        let row = self.client().query_opt(
+            "SELECT value FROM guild_settings WHERE guild_id = $1 AND key = $2",
+            &[&(guild_id as i64), &key],
+        )?;
+
+        Ok(row.map(|row| row.try_get(0)).transpose()?)
+    }
+
+    fn set_guild_setting(&self, guild_id: u64, key: &str, value: &str) -> Result<(), StorageError> {
+        self.client().execute(
+            "INSERT INTO guild_settings (guild_id, key, value) VALUES ($1, $2, $3)
+             ON CONFLICT (guild_id, key) DO UPDATE SET value = excluded.value",
+            &[&(guild_id as i64), &key, &value],
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_guild_setting(&self, guild_id: u64, key: &str) -> Result<(), StorageError> {
+        self.client().execute(
+            "DELETE FROM guild_settings WHERE guild_id = $1 AND key = $2",
+            &[&(guild_id as i64), &key],
+        )?;
+
+        Ok(())
+    }
+
+    fn user_link(&self, user_id: u64) -> Result<Option<UserLink>, StorageError> {
+        let row = self.client().query_opt(
+            "SELECT team_id, team_number, program_id FROM user_links WHERE user_id = $1",
+            &[&(user_id as i64)],
+        )?;
+
+        Ok(row
+            .map(|row| -> Result<UserLink, postgres::Error> {
+                Ok(UserLink {
+                    user_id,
+                    team_id: row.try_get(0)?,
+                    team_number: row.try_get(1)?,
+                    program_id: row.try_get(2)?,
+                })
+            })
+            .transpose()?)
+    }
+
+    fn set_user_link(&self, link: &UserLink) -> Result<(), StorageError> {
+        self.client().execute(
+            "INSERT INTO user_links (user_id, team_id, team_number, program_id) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id) DO UPDATE SET
+                team_id = excluded.team_id,
+                team_number = excluded.team_number,
+                program_id = excluded.program_id",
+            &[&(link.user_id as i64), &link.team_id, &link.team_number, &link.program_id],
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_user_link(&self, user_id: u64) -> Result<bool, StorageError> {
+        let removed = self.client().execute(
+            "DELETE FROM user_links WHERE user_id = $1",
+            &[&(user_id as i64)],
+        )?;
+
+        Ok(removed > 0)
+    }
+
+    fn watched_teams(&self) -> Result<Vec<(u64, WatchedTeam)>, StorageError> {
+        let rows = self.client().query("SELECT guild_id, data FROM watched_teams", &[])?;
+
+        rows.iter()
+            .map(|row| -> Result<(u64, WatchedTeam), StorageError> {
+                let guild_id: i64 = row.try_get(0)?;
+                let data: String = row.try_get(1)?;
+
+                Ok((guild_id as u64, serde_json::from_str(&data)?))
+            })
+            .collect()
+    }
+
+    fn save_watched_team(&self, guild_id: u64, watched: &WatchedTeam) -> Result<(), StorageError> {
+        let data = serde_json::to_string(watched)?;
+
+        self.client().execute(
+            "INSERT INTO watched_teams (guild_id, team_id, data) VALUES ($1, $2, $3)
+             ON CONFLICT (guild_id, team_id) DO UPDATE SET data = excluded.data",
+            &[&(guild_id as i64), &watched.team_id, &data],
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_watched_team(&self, guild_id: u64, team_id: i32) -> Result<(), StorageError> {
+        self.client().execute(
+            "DELETE FROM watched_teams WHERE guild_id = $1 AND team_id = $2",
+            &[&(guild_id as i64), &team_id],
+        )?;
+
+        Ok(())
+    }
+
+    fn cached_response(&self, key: &str) -> Result<Option<CachedResponse>, StorageError> {
+        let row = self.client().query_opt(
+            "SELECT value, fetched_at FROM api_cache WHERE key = $1",
+            &[&key],
+        )?;
+
+        Ok(row
+            .map(|row| -> Result<CachedResponse, postgres::Error> {
+                Ok(CachedResponse {
+                    value: row.try_get(0)?,
+                    fetched_at: row.try_get(1)?,
+                })
+            })
+            .transpose()?)
+    }
+
+    fn cache_response(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.client().execute(
+            "INSERT INTO api_cache (key, value, fetched_at) VALUES ($1, $2, $3)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value, fetched_at = excluded.fetched_at",
+            &[&key, &value, &unix_now()],
+        )?;
+
+        Ok(())
+    }
+
+    fn save_skills_snapshot(&self, season_id: i32, grade: &str, post_season: bool, snapshot: &SkillsSnapshot) -> Result<(), StorageError> {
+        let mut client = self.client();
+        let mut transaction = client.transaction()?;
+
+        let snapshot_id: i64 = transaction
+            .query_one(
+                "INSERT INTO skills_snapshots (season_id, grade, post_season, taken_at) VALUES ($1, $2, $3, $4) RETURNING id",
+                &[&season_id, &grade, &post_season, &snapshot.taken_at],
+            )?
+            .try_get(0)?;
+
+        let statement = transaction.prepare(
+            "INSERT INTO skills_snapshot_entries (snapshot_id, team_id, rank, max_driver, max_programming, score)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )?;
+        for entry in snapshot.entries.iter() {
+            transaction.execute(&statement, &[
+                &snapshot_id,
+                &entry.team_id,
+                &entry.rank,
+                &entry.max_driver,
+                &entry.max_programming,
+                &entry.score,
+            ])?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn team_skills_history(&self, team_id: i32, season_id: i32, grade: &str, post_season: bool) -> Result<Vec<SkillsHistoryPoint>, StorageError> {
+        let rows = self.client().query(
+            "SELECT snapshots.taken_at, entries.team_id, entries.rank, entries.max_driver, entries.max_programming, entries.score
+             FROM skills_snapshot_entries entries
+             JOIN skills_snapshots snapshots ON snapshots.id = entries.snapshot_id
+             WHERE entries.team_id = $1 AND snapshots.season_id = $2 AND snapshots.grade = $3 AND snapshots.post_season = $4
+             ORDER BY snapshots.taken_at",
+            &[&team_id, &season_id, &grade, &post_season],
+        )?;
+
+        Ok(rows.iter().map(skills_history_point).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn latest_skills_entries(&self, season_id: i32, grade: &str, post_season: bool) -> Result<Vec<SkillsHistoryPoint>, StorageError> {
+        let rows = self.client().query(
+            "SELECT DISTINCT ON (entries.team_id)
+                snapshots.taken_at, entries.team_id, entries.rank, entries.max_driver, entries.max_programming, entries.score
+             FROM skills_snapshot_entries entries
+             JOIN skills_snapshots snapshots ON snapshots.id = entries.snapshot_id
+             WHERE snapshots.season_id = $1 AND snapshots.grade = $2 AND snapshots.post_season = $3
+             ORDER BY entries.team_id, entries.snapshot_id DESC",
+            &[&season_id, &grade, &post_season],
+        )?;
+
+        Ok(rows.iter().map(skills_history_point).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn prune_skills_snapshots(&self, taken_before: i64) -> Result<(), StorageError> {
+        // Entries are removed along with their snapshot through `ON DELETE CASCADE`.
+        self.client().execute("DELETE FROM skills_snapshots WHERE taken_at < $1", &[&taken_before])?;
+
+        Ok(())
+    }
+
+    fn indexed_teams(&self) -> Result<Vec<IndexedTeam>, StorageError> {
+        let rows = self.client().query("SELECT number, name, program FROM team_index", &[])?;
+
+        Ok(rows
+            .iter()
+            .map(|row| -> Result<IndexedTeam, postgres::Error> {
+                Ok(IndexedTeam {
+                    number: row.try_get(0)?,
+                    name: row.try_get(1)?,
+                    program: row.try_get(2)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn index_teams(&self, teams: &[IndexedTeam]) -> Result<(), StorageError> {
+        let mut client = self.client();
+        let mut transaction = client.transaction()?;
+
+        let statement = transaction.prepare(
+            "INSERT INTO team_index (number, program, name) VALUES ($1, $2, $3)
+             ON CONFLICT (number, program) DO UPDATE SET name = excluded.name",
+        )?;
+        for team in teams.iter() {
+            transaction.execute(&statement, &[&team.number, &team.program, &team.name])?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn indexed_events(&self) -> Result<Vec<IndexedEvent>, StorageError> {
+        let rows = self.client().query("SELECT sku, name FROM event_index", &[])?;
+
+        Ok(rows
+            .iter()
+            .map(|row| -> Result<IndexedEvent, postgres::Error> {
+                Ok(IndexedEvent {
+                    sku: row.try_get(0)?,
+                    name: row.try_get(1)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn index_event(&self, event: &IndexedEvent) -> Result<(), StorageError> {
+        self.client().execute(
+            "INSERT INTO event_index (sku, name) VALUES ($1, $2)
+             ON CONFLICT (sku) DO UPDATE SET name = excluded.name",
+            &[&event.sku, &event.name],
+        )?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
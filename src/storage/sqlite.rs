@@ -0,0 +1,392 @@
+use std::{
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::api::skills_history::{SkillsHistoryPoint, SkillsSnapshot, SkillsSnapshotEntry};
+use crate::lookup_index::{IndexedEvent, IndexedTeam};
+use crate::storage::{CachedResponse, Repository, StorageError, UserLink};
+use crate::watchlist::WatchedTeam;
+
+/// Schema for every table, applied when the database is opened.
+///
+/// Tables are only ever added to, so `CREATE TABLE IF NOT EXISTS` is enough to bring an older database
+/// up to date.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS guild_settings (
+        guild_id INTEGER NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (guild_id, key)
+    );
+
+    CREATE TABLE IF NOT EXISTS user_links (
+        user_id INTEGER PRIMARY KEY,
+        team_id INTEGER NOT NULL,
+        team_number TEXT NOT NULL,
+        program_id INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS watched_teams (
+        guild_id INTEGER NOT NULL,
+        team_id INTEGER NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (guild_id, team_id)
+    );
+
+    CREATE TABLE IF NOT EXISTS api_cache (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        fetched_at INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS skills_snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        season_id INTEGER NOT NULL,
+        grade TEXT NOT NULL,
+        post_season INTEGER NOT NULL,
+        taken_at INTEGER NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS skills_snapshot_entries (
+        snapshot_id INTEGER NOT NULL REFERENCES skills_snapshots (id) ON DELETE CASCADE,
+        team_id INTEGER NOT NULL,
+        rank INTEGER NOT NULL,
+        max_driver INTEGER NOT NULL,
+        max_programming INTEGER NOT NULL,
+        score INTEGER NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS skills_snapshot_entries_team ON skills_snapshot_entries (team_id, snapshot_id);
+    CREATE INDEX IF NOT EXISTS skills_snapshot_entries_snapshot ON skills_snapshot_entries (snapshot_id);
+    CREATE INDEX IF NOT EXISTS skills_snapshots_taken_at ON skills_snapshots (taken_at);
+
+    CREATE TABLE IF NOT EXISTS team_index (
+        number TEXT NOT NULL,
+        program TEXT NOT NULL,
+        name TEXT NOT NULL,
+        PRIMARY KEY (number, program)
+    );
+
+    CREATE TABLE IF NOT EXISTS event_index (
+        sku TEXT PRIMARY KEY,
+        name TEXT NOT NULL
+    );
+";
+
+/// [`Repository`] implementation backed by an embedded SQLite database.
+#[derive(Debug)]
+pub struct SqliteStorage {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Opens (or creates) the database at `path`, and makes sure that every table exists.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Locks the database connection.
+    ///
+    /// A poisoned lock only means that another query panicked, which doesn't leave SQLite in a bad state,
+    /// so the connection is still handed out.
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Returns the current time as a Unix timestamp in seconds.
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs() as i64).unwrap_or_default()
+}
+
+impl Repository for SqliteStorage {
+    fn guild_setting(&self, guild_id: u64, key: &str) -> Result<Option<String>, StorageError> {
+        Ok(self.connection()
+            .query_row(
+                "SELECT value FROM guild_settings WHERE guild_id = ?1 AND key = ?2",
+                params![guild_id as i64, key],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn set_guild_setting(&self, guild_id: u64, key: &str, value: &str) -> Result<(), StorageError> {
+        self.connection().execute(
+            "INSERT INTO guild_settings (guild_id, key, value) VALUES (?1, ?2, ?3)
+             ON CONFLICT (guild_id, key) DO UPDATE SET value = excluded.value",
+            params![guild_id as i64, key, value],
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_guild_setting(&self, guild_id: u64, key: &str) -> Result<(), StorageError> {
+        self.connection().execute(
+            "DELETE FROM guild_settings WHERE guild_id = ?1 AND key = ?2",
+            params![guild_id as i64, key],
+        )?;
+
+        Ok(())
+    }
+
+    fn user_link(&self, user_id: u64) -> Result<Option<UserLink>, StorageError> {
+        Ok(self.connection()
+            .query_row(
+                "SELECT team_id, team_number, program_id FROM user_links WHERE user_id = ?1",
+                params![user_id as i64],
+                |row| Ok(UserLink {
+                    user_id,
+                    team_id: row.get(0)?,
+                    team_number: row.get(1)?,
+                    program_id: row.get(2)?,
+                }),
+            )
+            .optional()?)
+    }
+
+    fn set_user_link(&self, link: &UserLink) -> Result<(), StorageError> {
+        self.connection().execute(
+            "INSERT INTO user_links (user_id, team_id, team_number, program_id) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (user_id) DO UPDATE SET
+                team_id = excluded.team_id,
+                team_number = excluded.team_number,
+                program_id = excluded.program_id",
+            params![link.user_id as i64, link.team_id, link.team_number, link.program_id],
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_user_link(&self, user_id: u64) -> Result<bool, StorageError> {
+        let removed = self.connection().execute(
+            "DELETE FROM user_links WHERE user_id = ?1",
+            params![user_id as i64],
+        )?;
+
+        Ok(removed > 0)
+    }
+
+    fn watched_teams(&self) -> Result<Vec<(u64, WatchedTeam)>, StorageError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT guild_id, data FROM watched_teams")?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(guild_id, data)| Ok((guild_id as u64, serde_json::from_str(&data)?)))
+            .collect()
+    }
+
+    fn save_watched_team(&self, guild_id: u64, watched: &WatchedTeam) -> Result<(), StorageError> {
+        let data = serde_json::to_string(watched)?;
+
+        self.connection().execute(
+            "INSERT INTO watched_teams (guild_id, team_id, data) VALUES (?1, ?2, ?3)
+             ON CONFLICT (guild_id, team_id) DO UPDATE SET data = excluded.data",
+            params![guild_id as i64, watched.team_id, data],
+        )?;
+
+        Ok(())
+    }
+
+    fn remove_watched_team(&self, guild_id: u64, team_id: i32) -> Result<(), StorageError> {
+        self.connection().execute(
+            "DELETE FROM watched_teams WHERE guild_id = ?1 AND team_id = ?2",
+            params![guild_id as i64, team_id],
+        )?;
+
+        Ok(())
+    }
+
+    fn cached_response(&self, key: &str) -> Result<Option<CachedResponse>, StorageError> {
+        Ok(self.connection()
+            .query_row(
+                "SELECT value, fetched_at FROM api_cache WHERE key = ?1",
+                params![key],
+                |row| Ok(CachedResponse {
+                    value: row.get(0)?,
+                    fetched_at: row.get(1)?,
+                }),
+            )
+            .optional()?)
+    }
+
+    fn cache_response(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.connection().execute(
+            "INSERT INTO api_cache (key, value, fetched_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value, fetched_at = excluded.fetched_at",
+            params![key, value, unix_now()],
+        )?;
+
+        Ok(())
+    }
+
+    fn save_skills_snapshot(&self, season_id: i32, grade: &str, post_season: bool, snapshot: &SkillsSnapshot) -> Result<(), StorageError> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO skills_snapshots (season_id, grade, post_season, taken_at) VALUES (?1, ?2, ?3, ?4)",
+            params![season_id, grade, post_season, snapshot.taken_at],
+        )?;
+        let snapshot_id = transaction.last_insert_rowid();
+
+        {
+            let mut statement = transaction.prepare(
+                "INSERT INTO skills_snapshot_entries (snapshot_id, team_id, rank, max_driver, max_programming, score)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for entry in snapshot.entries.iter() {
+                statement.execute(params![
+                    snapshot_id,
+                    entry.team_id,
+                    entry.rank,
+                    entry.max_driver,
+                    entry.max_programming,
+                    entry.score,
+                ])?;
+            }
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn team_skills_history(&self, team_id: i32, season_id: i32, grade: &str, post_season: bool) -> Result<Vec<SkillsHistoryPoint>, StorageError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            "SELECT snapshots.taken_at, entries.rank, entries.max_driver, entries.max_programming, entries.score
+             FROM skills_snapshot_entries entries
+             JOIN skills_snapshots snapshots ON snapshots.id = entries.snapshot_id
+             WHERE entries.team_id = ?1 AND snapshots.season_id = ?2 AND snapshots.grade = ?3 AND snapshots.post_season = ?4
+             ORDER BY snapshots.taken_at",
+        )?;
+
+        let history = statement
+            .query_map(params![team_id, season_id, grade, post_season], |row| {
+                Ok(SkillsHistoryPoint {
+                    taken_at: row.get(0)?,
+                    entry: SkillsSnapshotEntry {
+                        team_id,
+                        rank: row.get(1)?,
+                        max_driver: row.get(2)?,
+                        max_programming: row.get(3)?,
+                        score: row.get(4)?,
+                    },
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(history)
+    }
+
+    fn latest_skills_entries(&self, season_id: i32, grade: &str, post_season: bool) -> Result<Vec<SkillsHistoryPoint>, StorageError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare(
+            "SELECT taken_at, team_id, rank, max_driver, max_programming, score FROM (
+                SELECT snapshots.taken_at, entries.*,
+                    ROW_NUMBER() OVER (PARTITION BY entries.team_id ORDER BY entries.snapshot_id DESC) AS recency
+                FROM skills_snapshot_entries entries
+                JOIN skills_snapshots snapshots ON snapshots.id = entries.snapshot_id
+                WHERE snapshots.season_id = ?1 AND snapshots.grade = ?2 AND snapshots.post_season = ?3
+             )
+             WHERE recency = 1",
+        )?;
+
+        let entries = statement
+            .query_map(params![season_id, grade, post_season], |row| {
+                Ok(SkillsHistoryPoint {
+                    taken_at: row.get(0)?,
+                    entry: SkillsSnapshotEntry {
+                        team_id: row.get(1)?,
+                        rank: row.get(2)?,
+                        max_driver: row.get(3)?,
+                        max_programming: row.get(4)?,
+                        score: row.get(5)?,
+                    },
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    fn prune_skills_snapshots(&self, taken_before: i64) -> Result<(), StorageError> {
+        // Entries are removed along with their snapshot through `ON DELETE CASCADE`.
+        self.connection().execute("DELETE FROM skills_snapshots WHERE taken_at < ?1", params![taken_before])?;
+
+        Ok(())
+    }
+
+    fn indexed_teams(&self) -> Result<Vec<IndexedTeam>, StorageError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT number, name, program FROM team_index")?;
+
+        let teams = statement
+            .query_map([], |row| {
+                Ok(IndexedTeam {
+                    number: row.get(0)?,
+                    name: row.get(1)?,
+                    program: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(teams)
+    }
+
+    fn index_teams(&self, teams: &[IndexedTeam]) -> Result<(), StorageError> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        {
+            let mut statement = transaction.prepare(
+                "INSERT INTO team_index (number, program, name) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (number, program) DO UPDATE SET name = excluded.name",
+            )?;
+            for team in teams.iter() {
+                statement.execute(params![team.number, team.program, team.name])?;
+            }
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn indexed_events(&self) -> Result<Vec<IndexedEvent>, StorageError> {
+        let connection = self.connection();
+        let mut statement = connection.prepare("SELECT sku, name FROM event_index")?;
+
+        let events = statement
+            .query_map([], |row| {
+                Ok(IndexedEvent {
+                    sku: row.get(0)?,
+                    name: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    fn index_event(&self, event: &IndexedEvent) -> Result<(), StorageError> {
+        self.connection().execute(
+            "INSERT INTO event_index (sku, name) VALUES (?1, ?2)
+             ON CONFLICT (sku) DO UPDATE SET name = excluded.name",
+            params![event.sku, event.name],
+        )?;
+
+        Ok(())
+    }
+}
\ No newline at end of file
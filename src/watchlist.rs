@@ -0,0 +1,373 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::{Serialize, Deserialize};
+use serenity::{
+    all::{ChannelId, Http},
+    builder::{CreateEmbed, CreateEmbedFooter, CreateMessage},
+    model::Color,
+};
+use tokio::sync::RwLock;
+
+use robotevents::{
+    RobotEvents,
+    query::{PaginatedQuery, SeasonsQuery, TeamAwardsQuery, TeamEventsQuery, TeamMatchesQuery, TeamsQuery},
+    schema::{Award, Event, Match, Season, Team},
+};
+
+use crate::api::skills::SkillsCache;
+use crate::storage::{self, Storage, StorageError};
+
+/// Maximum number of teams that a single guild can follow.
+pub const MAX_TEAMS_PER_GUILD: usize = 25;
+
+/// Discord caps embed descriptions at 4096 characters, so updates are split a bit before that.
+const MAX_DESCRIPTION_LENGTH: usize = 4000;
+
+/// Everything the poller saw about a team the last time it checked, used to tell what's new.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+struct TeamState {
+    season_id: i32,
+    award_ids: HashSet<i32>,
+    event_ids: HashSet<i32>,
+    scored_match_ids: HashSet<i32>,
+    skills_rank: Option<i32>,
+}
+
+/// A team on a guild's watchlist, and the channel that updates about it are posted to.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+pub struct WatchedTeam {
+    pub team_id: i32,
+    pub team_number: String,
+    pub program_id: i32,
+    pub channel_id: u64,
+
+    /// What the team looked like at the last poll. This is `None` for newly followed teams, whose first
+    /// poll only records a baseline so the channel isn't flooded with old results.
+    #[serde(default)]
+    state: Option<TeamState>,
+}
+
+/// The result of polling a single team.
+struct Observation {
+    team: Team,
+    season: Season,
+    awards: Vec<Award>,
+    events: Vec<Event>,
+    matches: Vec<Match>,
+    skills_rank: Option<i32>,
+}
+
+impl Observation {
+    fn state(&self) -> TeamState {
+        TeamState {
+            season_id: self.season.id,
+            award_ids: self.awards.iter().map(|award| award.id).collect(),
+            event_ids: self.events.iter().map(|event| event.id).collect(),
+            scored_match_ids: self.matches.iter().filter(|m| m.scored).map(|m| m.id).collect(),
+            skills_rank: self.skills_rank,
+        }
+    }
+
+    /// Describes everything that changed since `previous`, one line per change.
+    fn changes(&self, previous: &TeamState) -> Vec<String> {
+        // Everything is new again once a team moves on to a new season.
+        let empty_state = TeamState::default();
+        let previous = if previous.season_id == self.season.id { previous } else { &empty_state };
+
+        let mut changes = Vec::new();
+
+        for event in self.events.iter().filter(|event| !previous.event_ids.contains(&event.id)) {
+            changes.push(format!("🗓️ Registered for **{}** ({})", event.name, event.sku));
+        }
+
+        for award in self.awards.iter().filter(|award| !previous.award_ids.contains(&award.id)) {
+            changes.push(format!("🏆 Won **{}** at {}", award.title, award.event.name));
+        }
+
+        for robotevents_match in self.matches.iter().filter(|m| m.scored && !previous.scored_match_ids.contains(&m.id)) {
+            let Some(alliance) = robotevents_match.alliances
+                .iter()
+                .find(|alliance| alliance.teams.iter().any(|t| t.team.id == self.team.id))
+            else {
+                continue;
+            };
+            let opposing_alliance = robotevents_match.alliances
+                .iter()
+                .find(|other| other.teams.iter().all(|t| t.team.id != self.team.id));
+
+            changes.push(match opposing_alliance {
+                Some(opposing_alliance) => format!(
+                    "⚔️ {} at {}: **{}-{}** ({})",
+                    robotevents_match.name,
+                    robotevents_match.event.name,
+                    alliance.score,
+                    opposing_alliance.score,
+                    match alliance.score.cmp(&opposing_alliance.score) {
+                        std::cmp::Ordering::Greater => "W",
+                        std::cmp::Ordering::Less => "L",
+                        std::cmp::Ordering::Equal => "T",
+                    }
+                ),
+                None => format!(
+                    "⚔️ {} at {}: **{}**",
+                    robotevents_match.name, robotevents_match.event.name, alliance.score
+                ),
+            });
+        }
+
+        match (previous.skills_rank, self.skills_rank) {
+            (Some(old), Some(new)) if new < old => changes.push(format!("📈 Skills rank rose from **#{old}** to **#{new}**")),
+            (Some(old), Some(new)) if new > old => changes.push(format!("📉 Skills rank fell from **#{old}** to **#{new}**")),
+            (None, Some(new)) => changes.push(format!("📈 Now ranked **#{new}** in skills")),
+            _ => {},
+        }
+
+        changes
+    }
+}
+
+/// Teams followed by each guild, keyed by guild ID.
+///
+/// If the watchlist is backed by [`Storage`], every change is saved so that it survives restarts.
+#[derive(Default, Debug, Clone)]
+pub struct Watchlist {
+    storage: Option<Storage>,
+    guilds: Arc<RwLock<HashMap<u64, Vec<WatchedTeam>>>>,
+}
+
+impl Watchlist {
+    /// Loads every guild's followed teams from storage.
+    pub async fn load(storage: Storage) -> Result<Self, StorageError> {
+        let mut guilds: HashMap<u64, Vec<WatchedTeam>> = HashMap::new();
+        for (guild_id, watched) in storage::run(&storage, |storage| storage.watched_teams()).await? {
+            guilds.entry(guild_id).or_default().push(watched);
+        }
+
+        Ok(Self {
+            storage: Some(storage),
+            guilds: Arc::new(RwLock::new(guilds)),
+        })
+    }
+
+    /// Saves a followed team to storage, if the watchlist has any.
+    async fn save(&self, guild_id: u64, watched: WatchedTeam) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+
+        if let Err(err) = storage::run(storage, move |storage| storage.save_watched_team(guild_id, &watched)).await {
+            println!("Failed to save watchlist: {err}");
+        }
+    }
+
+    /// Returns every team followed by a guild.
+    pub async fn teams(&self, guild_id: u64) -> Vec<WatchedTeam> {
+        self.guilds.read().await.get(&guild_id).cloned().unwrap_or_default()
+    }
+
+    /// Adds a team to a guild's watchlist, or moves its updates to a new channel if it's already followed.
+    ///
+    /// Returns `false` if the guild's watchlist is already full.
+    pub async fn follow(&self, guild_id: u64, team: &Team, channel_id: u64) -> bool {
+        let watched = {
+            let mut guilds = self.guilds.write().await;
+            let teams = guilds.entry(guild_id).or_default();
+
+            if let Some(watched) = teams.iter_mut().find(|watched| watched.team_id == team.id) {
+                watched.channel_id = channel_id;
+                watched.clone()
+            } else if teams.len() >= MAX_TEAMS_PER_GUILD {
+                return false;
+            } else {
+                let watched = WatchedTeam {
+                    team_id: team.id,
+                    team_number: team.number.clone(),
+                    program_id: team.program.id,
+                    channel_id,
+                    state: None,
+                };
+                teams.push(watched.clone());
+                watched
+            }
+        };
+
+        self.save(guild_id, watched).await;
+        true
+    }
+
+    /// Removes a team from a guild's watchlist by its number.
+    ///
+    /// Returns the removed team, or `None` if the guild wasn't following it.
+    pub async fn unfollow(&self, guild_id: u64, team_number: &str) -> Option<WatchedTeam> {
+        let removed = {
+            let mut guilds = self.guilds.write().await;
+            let teams = guilds.get_mut(&guild_id)?;
+            let idx = teams.iter().position(|watched| watched.team_number.eq_ignore_ascii_case(team_number))?;
+
+            teams.remove(idx)
+        };
+
+        if let Some(storage) = &self.storage {
+            let team_id = removed.team_id;
+            if let Err(err) = storage::run(storage, move |storage| storage.remove_watched_team(guild_id, team_id)).await {
+                println!("Failed to save watchlist: {err}");
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Fetches the current season's awards, events, matches and skills rank of a watched team.
+    ///
+    /// Returns `None` if the team or its program's current season can't be found.
+    async fn observe(
+        watched: &WatchedTeam,
+        robotevents: &RobotEvents,
+        skills_cache: &SkillsCache,
+        seasons: &[Season],
+    ) -> Result<Option<Observation>, reqwest::Error> {
+        // Teams can't change programs, so the latest season of the team's program is the current one.
+        let Some(season) = seasons
+            .iter()
+            .filter(|season| season.program.id == watched.program_id)
+            .max_by_key(|season| season.years_start)
+            .cloned()
+        else {
+            return Ok(None);
+        };
+
+        let Some(team) = robotevents
+            .teams(TeamsQuery::new().number(watched.team_number.clone()).program(watched.program_id))
+            .await?
+            .data
+            .into_iter()
+            .find(|team| team.id == watched.team_id)
+        else {
+            return Ok(None);
+        };
+
+        let awards = team.awards(robotevents, TeamAwardsQuery::new().season(season.id).per_page(250)).await?.data;
+        let events = team.events(robotevents, TeamEventsQuery::new().season(season.id).per_page(250)).await?.data;
+        let matches = team.matches(robotevents, TeamMatchesQuery::new().season(season.id).per_page(250)).await?.data;
+        let skills_rank = skills_cache
+            .get_team_ranking(&team, season.id, false, robotevents)
+            .await?
+            .map(|ranking| ranking.ranking.rank);
+
+        Ok(Some(Observation {
+            team,
+            season,
+            awards,
+            events,
+            matches,
+            skills_rank,
+        }))
+    }
+
+    /// Checks every followed team for changes once, and posts any updates to the channels they're bound to.
+    async fn poll(&self, http: &Http, robotevents: &RobotEvents, skills_cache: &SkillsCache, seasons: &[Season]) {
+        let watched_teams = self.guilds
+            .read()
+            .await
+            .iter()
+            .flat_map(|(guild_id, teams)| teams.iter().map(|watched| (*guild_id, watched.clone())))
+            .collect::<Vec<_>>();
+
+        // Guilds that follow the same team share one set of requests.
+        let mut observations: HashMap<i32, Option<Observation>> = HashMap::new();
+
+        for (guild_id, watched) in watched_teams {
+            if let Entry::Vacant(entry) = observations.entry(watched.team_id) {
+                let observation = match Self::observe(&watched, robotevents, skills_cache, seasons).await {
+                    Ok(observation) => observation,
+                    Err(err) => {
+                        println!("Failed to poll team {} for watchlist updates: {err}", watched.team_number);
+                        None
+                    },
+                };
+                entry.insert(observation);
+            }
+            let Some(observation) = &observations[&watched.team_id] else {
+                continue;
+            };
+
+            if let Some(previous) = &watched.state {
+                let changes = observation.changes(previous);
+
+                // Embed descriptions are capped at 4096 characters, so long lists of changes (e.g. after a
+                // season rollover) are split across several messages.
+                let mut pages: Vec<String> = Vec::new();
+                for change in changes {
+                    match pages.last_mut() {
+                        Some(page) if page.len() + change.len() < MAX_DESCRIPTION_LENGTH => {
+                            page.push('\n');
+                            page.push_str(&change);
+                        },
+                        _ => pages.push(change),
+                    }
+                }
+
+                for page in pages {
+                    let embed = CreateEmbed::new()
+                        .title(format!("{} Updates", watched.team_number))
+                        .description(page)
+                        .footer(CreateEmbedFooter::new(format!("{} | Use /unfollow to stop these updates", observation.season.name)))
+                        .color(match watched.program_id {
+                            1 | 4 => Color::from_rgb(210, 38, 48), // VRC/VEXU
+                            41 => Color::from_rgb(0, 119, 200), // VIQC
+                            57 => Color::from_rgb(91, 91, 91), // VAIC
+                            _ => Default::default(),
+                        });
+
+                    if let Err(err) = ChannelId::new(watched.channel_id).send_message(http, CreateMessage::new().embed(embed)).await {
+                        println!("Failed to post watchlist update for {}: {err}", watched.team_number);
+                        break;
+                    }
+                }
+            }
+
+            // The team may have been unfollowed while it was being polled, in which case it isn't saved
+            // again. The lock is held until the save finishes so that an unfollow can't slip in between.
+            let mut guilds = self.guilds.write().await;
+            let entry = guilds
+                .get_mut(&guild_id)
+                .and_then(|teams| teams.iter_mut().find(|entry| entry.team_id == watched.team_id));
+            if let Some(entry) = entry {
+                entry.state = Some(observation.state());
+                self.save(guild_id, entry.clone()).await;
+            }
+        }
+    }
+
+    /// Spawns a task that polls every followed team for changes at a fixed interval.
+    ///
+    /// `seasons` is used to work out the current season of each team's program. It's refreshed before every
+    /// poll so that followed teams move on to new seasons, and the last list is kept if that fails.
+    pub fn spawn_poller(
+        &self,
+        http: Arc<Http>,
+        robotevents: RobotEvents,
+        skills_cache: SkillsCache,
+        mut seasons: Vec<Season>,
+        interval: Duration,
+    ) {
+        let watchlist = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+
+                match robotevents.seasons(SeasonsQuery::default().per_page(250)).await {
+                    Ok(season_list) => seasons = season_list.data,
+                    Err(err) => println!("Failed to refresh season list for watchlist updates: {err}"),
+                }
+
+                watchlist.poll(&http, &robotevents, &skills_cache, &seasons).await;
+            }
+        });
+    }
+}
\ No newline at end of file